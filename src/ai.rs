@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::{
+	app::{App, Plugin, PreUpdate},
+	ecs::{
+		component::Component,
+		entity::Entity,
+		query::{Added, With},
+		schedule::IntoSystemConfigs,
+		system::{Commands, Query, Res, Resource},
+	},
+};
+
+use crate::{
+	arena::{Arena, BoundaryMode, WallLayout},
+	autopilot::{Autopilot, HamiltonianCycle},
+	components::{Direction, DirectionQueue, Food, Position, Snake, SnakeHead, Wall},
+	movement::PlayerCount,
+	round::restart_round,
+	tick::{GameTick, TickSet},
+};
+
+/// What a [`Strategy`] sees of the board at the start of a tick.
+pub struct Board {
+	pub arena: Arena,
+	/// Cells a head moving into this tick would crash on, walls and snakes. Tails are left
+	/// out, they move away.
+	pub blocked: HashSet<Position>,
+	pub food: Vec<Position>,
+}
+impl Board {
+	/// `bodies` run from the head to the tail.
+	pub fn new<'a>(
+		arena: Arena,
+		bodies: impl IntoIterator<Item = &'a [Position]>,
+		walls: impl IntoIterator<Item = Position>,
+		food: Vec<Position>,
+	) -> Self {
+		Self {
+			arena,
+			blocked: bodies
+				.into_iter()
+				.flat_map(|body| &body[..body.len().saturating_sub(1)])
+				.copied()
+				.chain(walls)
+				.collect(),
+			food,
+		}
+	}
+
+	/// The cell one step from `position`, `None` off the board.
+	pub fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+		let next = match direction {
+			Direction::Left => Position {
+				x: position.x - 1,
+				..position
+			},
+			Direction::Right => Position {
+				x: position.x + 1,
+				..position
+			},
+			Direction::Up => Position {
+				y: position.y + 1,
+				..position
+			},
+			Direction::Down => Position {
+				y: position.y - 1,
+				..position
+			},
+		};
+		match self.arena.boundary {
+			BoundaryMode::Wrap => Some(self.arena.wrap(next)),
+			BoundaryMode::Walls => Some(next).filter(|next| self.arena.contains(*next)),
+		}
+	}
+
+	pub fn is_free(&self, position: Position) -> bool {
+		!self.blocked.contains(&position)
+	}
+
+	/// The food fewest steps from `position`, as the crow flies.
+	pub fn closest_food(&self, position: Position) -> Option<Position> {
+		self.food
+			.iter()
+			.copied()
+			.min_by_key(|food| food.distance(position))
+	}
+
+	pub fn food_distance(&self, position: Position) -> Option<i32> {
+		self.closest_food(position)
+			.map(|food| food.distance(position))
+	}
+
+	/// Directions `snake` can take this tick without crashing.
+	pub fn safe_moves(&self, snake: &SnakeView) -> Vec<Direction> {
+		Direction::ALL
+			.into_iter()
+			.filter(|direction| *direction != snake.direction.oppsite())
+			.filter(|direction| {
+				self.step(snake.head(), *direction)
+					.is_some_and(|next| self.is_free(next))
+			})
+			.collect()
+	}
+
+	/// First step of a shortest path over free cells from the head to a cell matching `goal`.
+	pub fn first_step_towards(
+		&self,
+		snake: &SnakeView,
+		goal: impl Fn(Position) -> bool,
+	) -> Option<Direction> {
+		let mut first_steps = HashMap::from([(snake.head(), None)]);
+		let mut frontier = VecDeque::new();
+		for direction in self.safe_moves(snake) {
+			let next = self.step(snake.head(), direction)?;
+			if first_steps.insert(next, Some(direction)).is_none() {
+				frontier.push_back(next);
+			}
+		}
+		while let Some(position) = frontier.pop_front() {
+			let first_step = first_steps[&position];
+			if goal(position) {
+				return first_step;
+			}
+			for direction in Direction::ALL {
+				let Some(next) = self.step(position, direction) else {
+					continue;
+				};
+				if self.is_free(next) && !first_steps.contains_key(&next) {
+					first_steps.insert(next, first_step);
+					frontier.push_back(next);
+				}
+			}
+		}
+		None
+	}
+}
+
+/// A snake as a [`Strategy`] sees it.
+pub struct SnakeView {
+	pub direction: Direction,
+	/// Positions from the head to the tail.
+	pub body: Vec<Position>,
+}
+impl SnakeView {
+	pub fn head(&self) -> Position {
+		self.body[0]
+	}
+
+	pub fn tail(&self) -> Position {
+		self.body[self.body.len() - 1]
+	}
+}
+
+/// Decides where a computer-controlled snake goes next.
+pub trait Strategy: Send + Sync + 'static {
+	/// `None` keeps going straight.
+	fn next_direction(&self, board: &Board, snake: &SnakeView) -> Option<Direction>;
+}
+
+/// Heads for the closest food as the crow flies, without looking further than one step.
+pub struct Greedy;
+impl Strategy for Greedy {
+	fn next_direction(&self, board: &Board, snake: &SnakeView) -> Option<Direction> {
+		// `min_by_key` keeps the first of equals, so going straight wins ties.
+		let mut moves = board.safe_moves(snake);
+		moves.sort_by_key(|direction| *direction != snake.direction);
+		moves.into_iter().min_by_key(|direction| {
+			board
+				.step(snake.head(), *direction)
+				.map_or(i32::MAX, |next| {
+					board.food_distance(next).unwrap_or_default()
+				})
+		})
+	}
+}
+
+/// Follows the shortest safe path to the closest food, and chases its tail when there is none.
+pub struct ShortestPath;
+impl Strategy for ShortestPath {
+	fn next_direction(&self, board: &Board, snake: &SnakeView) -> Option<Direction> {
+		board
+			.first_step_towards(snake, |position| board.food.contains(&position))
+			.or_else(|| TailChaser.next_direction(board, snake))
+	}
+}
+
+/// Survives as long as it can by following its own tail, ignoring food.
+pub struct TailChaser;
+impl Strategy for TailChaser {
+	fn next_direction(&self, board: &Board, snake: &SnakeView) -> Option<Direction> {
+		let tail = snake.tail();
+		board
+			.first_step_towards(snake, |position| position == tail)
+			.or_else(|| board.safe_moves(snake).first().copied())
+	}
+}
+
+/// The built-in strategies, by their name in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+	Greedy,
+	ShortestPath,
+	TailChaser,
+}
+impl StrategyKind {
+	pub fn parse(name: &str) -> Option<Self> {
+		match name {
+			"greedy" => Some(Self::Greedy),
+			"path" => Some(Self::ShortestPath),
+			"tail" => Some(Self::TailChaser),
+			_ => None,
+		}
+	}
+
+	pub fn strategy(self) -> Box<dyn Strategy> {
+		match self {
+			Self::Greedy => Box::new(Greedy),
+			Self::ShortestPath => Box::new(ShortestPath),
+			Self::TailChaser => Box::new(TailChaser),
+		}
+	}
+}
+
+/// Computer-controlled players, they take the last player slots of a round.
+#[derive(Resource, Default, Clone)]
+pub struct Opponents(pub Vec<StrategyKind>);
+
+/// Steers the snake it is on.
+#[derive(Component)]
+pub struct AiController(pub Box<dyn Strategy>);
+
+/// Lets [`Opponents`], and the [`Autopilot`], steer their snakes.
+pub struct AiPlugin;
+impl Plugin for AiPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<Opponents>()
+			.init_resource::<Autopilot>()
+			.add_systems(PreUpdate, attach_ai_controllers.after(restart_round))
+			.add_systems(GameTick, ai_steering.in_set(TickSet::Input));
+	}
+}
+
+fn attach_ai_controllers(
+	mut commands: Commands,
+	opponents: Res<Opponents>,
+	autopilot: Res<Autopilot>,
+	players: Res<PlayerCount>,
+	arena: Res<Arena>,
+	walls: Res<WallLayout>,
+	snakes: Query<(Entity, &Snake), Added<Snake>>,
+) {
+	let humans = players.humans(&opponents);
+	for (entity, snake) in snakes.iter() {
+		if autopilot.0 && snake.player == 0 {
+			if let Some(cycle) = HamiltonianCycle::new(&arena, &walls) {
+				commands
+					.entity(entity)
+					.insert(AiController(Box::new(cycle)));
+			}
+		} else if let Some(kind) = snake
+			.player
+			.checked_sub(humans)
+			.and_then(|index| opponents.0.get(index))
+		{
+			commands
+				.entity(entity)
+				.insert(AiController(kind.strategy()));
+		}
+	}
+}
+
+pub fn ai_steering(
+	arena: Res<Arena>,
+	snakes: Query<&Snake>,
+	mut controlled: Query<(&Snake, &SnakeHead, &AiController, &mut DirectionQueue)>,
+	positions: Query<&Position>,
+	food: Query<&Position, With<Food>>,
+	walls: Query<&Position, With<Wall>>,
+) {
+	let position = |segment| positions.get(segment).ok().copied();
+	let bodies = snakes
+		.iter()
+		.map(|snake| snake.body(position))
+		.collect::<Vec<Vec<Position>>>();
+	let board = Board::new(
+		*arena,
+		bodies.iter().map(Vec::as_slice),
+		walls.iter().copied(),
+		food.iter().copied().collect(),
+	);
+	for (snake, head, controller, mut queue) in controlled.iter_mut() {
+		let view = SnakeView {
+			direction: head.direction,
+			body: snake.body(position),
+		};
+		if let Some(direction) = controller.0.next_direction(&board, &view) {
+			queue.push(direction, head.direction);
+		}
+	}
+}
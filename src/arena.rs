@@ -0,0 +1,73 @@
+use bevy::ecs::system::Resource;
+
+use crate::components::Position;
+
+pub const MIN_ARENA_SIZE: u32 = 4;
+
+/// Cells of the arena taken by [`Wall`](crate::components::Wall)s every round.
+#[derive(Resource, Default, Clone)]
+pub struct WallLayout(pub Vec<Position>);
+
+/// What happens when the head leaves the arena.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoundaryMode {
+	/// The edges are walls, leaving the arena ends the round.
+	#[default]
+	Walls,
+	/// The arena is a torus, the head comes back in on the opposite edge.
+	Wrap,
+}
+
+#[derive(Resource, Clone, Copy)]
+pub struct Arena {
+	pub width: u32,
+	pub height: u32,
+	/// On-screen size of a cell in pixels, used for the initial window size.
+	pub cell_size: f32,
+	pub boundary: BoundaryMode,
+}
+impl Default for Arena {
+	fn default() -> Self {
+		Self {
+			width: 10,
+			height: 10,
+			cell_size: 50.0,
+			boundary: BoundaryMode::default(),
+		}
+	}
+}
+impl Arena {
+	/// Maps a position that stepped off an edge back onto the opposite edge.
+	pub fn wrap(&self, pos: Position) -> Position {
+		Position {
+			x: pos.x.rem_euclid(self.width as i32),
+			y: pos.y.rem_euclid(self.height as i32),
+		}
+	}
+
+	pub fn contains(&self, pos: Position) -> bool {
+		pos.x >= 0 && pos.y >= 0 && (pos.x as u32) < self.width && (pos.y as u32) < self.height
+	}
+
+	pub fn cells(&self) -> impl Iterator<Item = Position> {
+		let (width, height) = (self.width as i32, self.height as i32);
+		(0..height).flat_map(move |y| (0..width).map(move |x| Position { x, y }))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Arena;
+	use crate::components::Position;
+
+	#[test]
+	fn wrap_moves_to_the_opposite_edge() {
+		let arena = Arena::default();
+		let wrap = |x, y| arena.wrap(Position { x, y });
+		assert!(wrap(-1, 3) == Position { x: 9, y: 3 });
+		assert!(wrap(10, 3) == Position { x: 0, y: 3 });
+		assert!(wrap(3, -1) == Position { x: 3, y: 9 });
+		assert!(wrap(3, 10) == Position { x: 3, y: 0 });
+		assert!(wrap(3, 4) == Position { x: 3, y: 4 });
+	}
+}
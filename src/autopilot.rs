@@ -0,0 +1,150 @@
+use bevy::ecs::{schedule::State, system::Resource};
+
+use crate::{
+	ai::{Board, Opponents, SnakeView, Strategy},
+	arena::{Arena, WallLayout},
+	components::{Direction, Position},
+	headless::headless_app,
+	movement::{GameOverCause, PlayerCount},
+	round::LastGameOver,
+	score::Score,
+	settings::Settings,
+	tick::TickCounter,
+	AppState,
+};
+
+/// Whether the first player's snake drives itself along a [`HamiltonianCycle`].
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Autopilot(pub bool);
+
+/// A closed path through every cell of the arena. Following it never crashes and, with
+/// food eventually spawning on every free cell, fills the board.
+pub struct HamiltonianCycle {
+	width: i32,
+	/// Position of every cell along the cycle, by `y * width + x`.
+	index: Vec<usize>,
+}
+impl HamiltonianCycle {
+	/// Rows are swept back and forth from the second column on, and the first column leads
+	/// back to the start. `None` on boards with an odd number of cells, where no cycle exists,
+	/// and on boards with walls, which the sweep would run into.
+	pub fn new(arena: &Arena, walls: &WallLayout) -> Option<Self> {
+		if !walls.0.is_empty() {
+			return None;
+		}
+		let (width, height) = (arena.width as i32, arena.height as i32);
+		// Sweep along whichever side has an even length, so the sweep ends next to the
+		// first column.
+		let (rows, columns) = match (width % 2, height % 2) {
+			(_, 0) => (height, width),
+			(0, _) => (width, height),
+			_ => return None,
+		};
+		let mut cells = Vec::with_capacity((width * height) as usize);
+		for row in 0..rows {
+			let sweep = (1..columns).map(move |column| (column, row));
+			match row % 2 {
+				0 => cells.extend(sweep),
+				_ => cells.extend(sweep.rev()),
+			}
+		}
+		cells.extend((0..rows).rev().map(|row| (0, row)));
+		let mut index = vec![0; cells.len()];
+		for (i, (column, row)) in cells.into_iter().enumerate() {
+			let (x, y) = if rows == height {
+				(column, row)
+			} else {
+				(row, column)
+			};
+			index[(y * width + x) as usize] = i;
+		}
+		Some(Self { width, index })
+	}
+
+	fn index(&self, position: Position) -> usize {
+		self.index[(position.y * self.width + position.x) as usize]
+	}
+
+	/// Steps along the cycle from `from` to `to`.
+	fn distance(&self, from: Position, to: Position) -> usize {
+		let len = self.index.len();
+		(self.index(to) + len - self.index(from)) % len
+	}
+}
+impl Strategy for HamiltonianCycle {
+	/// Follows the cycle, cutting ahead towards food while the snake is short enough that
+	/// the cells skipped can't trap it. A shortcut never passes the food or the tail, so the
+	/// body stays in cycle order.
+	fn next_direction(&self, board: &Board, snake: &SnakeView) -> Option<Direction> {
+		let head = snake.head();
+		// Every food eaten on the way holds the tail back one tick.
+		let room = self
+			.distance(head, snake.tail())
+			.saturating_sub(board.food.len());
+		let food = board
+			.food
+			.iter()
+			.map(|food| self.distance(head, *food))
+			.min();
+		let shortcuts = snake.body.len() * 2 < self.index.len();
+		let moves = board.safe_moves(snake);
+		moves
+			.iter()
+			.filter_map(|direction| {
+				let ahead = self.distance(head, board.step(head, *direction)?);
+				let allowed = ahead == 1
+					|| (shortcuts && ahead < room && food.is_some_and(|food| ahead <= food));
+				allowed.then_some((*direction, ahead))
+			})
+			.max_by_key(|(_, ahead)| *ahead)
+			.map(|(direction, _)| direction)
+			// Until the body lines up with the cycle after the start, the next cell may be
+			// taken.
+			.or_else(|| moves.first().copied())
+	}
+}
+
+/// How an [`autopilot_run`] ended.
+#[derive(Debug, Clone, Copy)]
+pub struct AutopilotReport {
+	/// The snake covered every cell.
+	pub filled: bool,
+	pub ticks: u64,
+	pub length: usize,
+}
+
+/// Plays one headless single-player round on autopilot, giving up after `max_ticks`. `None`
+/// when the board has no [`HamiltonianCycle`] to follow.
+///
+/// ```no_run
+/// use block_bite::{autopilot::autopilot_run, settings::Settings};
+///
+/// let report = autopilot_run(Settings::default(), 100_000).unwrap();
+/// println!("filled: {}, ticks: {}", report.filled, report.ticks);
+/// ```
+pub fn autopilot_run(mut settings: Settings, max_ticks: u64) -> Option<AutopilotReport> {
+	HamiltonianCycle::new(&settings.arena, &settings.walls)?;
+	settings.autopilot = true;
+	settings.players = PlayerCount(1);
+	settings.opponents = Opponents::default();
+	settings.replay = None;
+	let mut app = headless_app(settings);
+	loop {
+		app.update();
+		let ticks = app.world.resource::<TickCounter>().0;
+		if *app.world.resource::<State<AppState>>().get() == AppState::GameOver
+			|| ticks >= max_ticks
+		{
+			return Some(AutopilotReport {
+				filled: app
+					.world
+					.resource::<LastGameOver>()
+					.0
+					.as_ref()
+					.is_some_and(|event| event.cause == GameOverCause::BoardFull),
+				ticks,
+				length: app.world.resource::<Score>().player(0).length,
+			});
+		}
+	}
+}
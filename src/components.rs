@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+
+use bevy::ecs::{component::Component, entity::Entity};
+
+const INPUT_QUEUE_LEN: usize = 3;
+
+/// One player's snake, kept on its head entity.
+#[derive(Component)]
+pub struct Snake {
+	/// Index of the player steering it, from 0.
+	pub player: usize,
+	/// Segment entities from the head to the tail.
+	pub segments: Vec<Entity>,
+	/// Where the tail was before the last move, new segments grow there.
+	pub last_tail_position: Option<Position>,
+}
+impl Snake {
+	/// Segment positions from the head to the tail, skipping those `position` can't find.
+	pub fn body(&self, position: impl FnMut(Entity) -> Option<Position>) -> Vec<Position> {
+		self.segments.iter().copied().filter_map(position).collect()
+	}
+}
+
+#[derive(Component)]
+pub struct SnakeHead {
+	/// Direction the head moved on the last tick.
+	pub direction: Direction,
+}
+
+/// Turns pressed since the last tick, applied one per movement tick.
+#[derive(Component, Default)]
+pub struct DirectionQueue(VecDeque<Direction>);
+impl DirectionQueue {
+	/// Queues a turn unless the queue is full or the turn repeats or reverses the one before it.
+	pub fn push(&mut self, direction: Direction, moving: Direction) {
+		let previous = self.0.back().copied().unwrap_or(moving);
+		if self.0.len() < INPUT_QUEUE_LEN
+			&& direction != previous
+			&& direction != previous.oppsite()
+		{
+			self.0.push_back(direction);
+		}
+	}
+
+	pub fn pop(&mut self) -> Option<Direction> {
+		self.0.pop_front()
+	}
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+	pub x: i32,
+	pub y: i32,
+}
+impl Position {
+	/// Steps to `other` along the grid, ignoring whatever is in the way.
+	pub fn distance(self, other: Position) -> i32 {
+		(self.x - other.x).abs() + (self.y - other.y).abs()
+	}
+}
+
+#[derive(Component)]
+pub struct Size {
+	pub width: f32,
+	pub height: f32,
+}
+impl Size {
+	pub fn square(x: f32) -> Self {
+		Self {
+			width: x,
+			height: x,
+		}
+	}
+}
+
+#[derive(Component)]
+pub struct Food;
+
+/// A cell inside the arena that ends the round for a snake running into it.
+#[derive(Component)]
+pub struct Wall;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Direction {
+	Left,
+	Up,
+	Right,
+	Down,
+}
+impl Direction {
+	pub const ALL: [Direction; 4] = [
+		Direction::Up,
+		Direction::Down,
+		Direction::Left,
+		Direction::Right,
+	];
+
+	/// Lowercase name, as written in the key-map and replay files.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Left => "left",
+			Self::Up => "up",
+			Self::Right => "right",
+			Self::Down => "down",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|direction| direction.name() == name)
+	}
+
+	pub fn oppsite(self) -> Self {
+		match self {
+			Self::Left => Self::Right,
+			Self::Right => Self::Left,
+			Self::Up => Self::Down,
+			Self::Down => Self::Up,
+		}
+	}
+}
+
+#[derive(Component)]
+pub struct SnakeSegment;
+
+#[cfg(test)]
+mod tests {
+	use super::{Direction, DirectionQueue};
+
+	#[test]
+	fn turns_within_one_tick_are_kept_in_order() {
+		let mut queue = DirectionQueue::default();
+		queue.push(Direction::Up, Direction::Right);
+		queue.push(Direction::Left, Direction::Right);
+		assert_eq!(queue.pop(), Some(Direction::Up));
+		assert_eq!(queue.pop(), Some(Direction::Left));
+		assert_eq!(queue.pop(), None);
+	}
+
+	#[test]
+	fn quick_turns_never_reverse_the_snake() {
+		let mut queue = DirectionQueue::default();
+		queue.push(Direction::Left, Direction::Right);
+		queue.push(Direction::Down, Direction::Right);
+		assert_eq!(queue.pop(), Some(Direction::Down));
+		assert_eq!(queue.pop(), None);
+	}
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use bevy::{
+	app::{App, AppExit, Plugin, PreUpdate, Startup, Update},
+	ecs::{
+		event::{EventReader, EventWriter},
+		query::Without,
+		schedule::{
+			common_conditions::{in_state, not, resource_exists},
+			IntoSystemConfigs, NextState, State,
+		},
+		system::{Local, Query, Res, ResMut},
+	},
+	input::{
+		gamepad::{
+			Gamepad, GamepadAxis, GamepadAxisType, GamepadConnection, GamepadConnectionEvent,
+			Gamepads,
+		},
+		keyboard::KeyCode,
+		Axis, ButtonInput, InputSystem,
+	},
+	log::info,
+	time::{Time, Virtual},
+	window::WindowFocused,
+};
+
+use crate::{
+	ai::{AiController, Opponents},
+	components::{Direction, DirectionQueue, Snake, SnakeHead},
+	keymap::{key_map_path, Action, ActionInput, KeyMap},
+	movement::{PlayerCount, MAX_PLAYERS},
+	replay::ReplayPlayback,
+	round::{restart_allowed, RestartEvent},
+	AppState,
+};
+
+/// How far the left stick has to lean before it steers.
+const STICK_DEADZONE: f32 = 0.5;
+
+const MIN_PLAYBACK_SPEED: f32 = 0.25;
+const MAX_PLAYBACK_SPEED: f32 = 8.0;
+
+/// Keyboard and gamepad controls, mapped through the [`KeyMap`], for steering and for moving
+/// between game states.
+pub struct ControlsPlugin;
+impl Plugin for ControlsPlugin {
+	fn build(&self, app: &mut App) {
+		// Steering is read in `PreUpdate` so a turn reaches the queue before the ticks of the
+		// same frame run.
+		app.insert_resource(KeyMap::default())
+			.add_systems(Startup, load_key_map)
+			.add_systems(
+				PreUpdate,
+				snake_movement_input
+					.after(InputSystem)
+					.run_if(in_state(AppState::Playing))
+					.run_if(not(resource_exists::<ReplayPlayback>)),
+			)
+			.add_systems(
+				Update,
+				(
+					log_gamepad_connections,
+					playback_speed_input.run_if(resource_exists::<ReplayPlayback>),
+					main_menu_input.run_if(in_state(AppState::MainMenu)),
+					pause_input,
+					pause_on_focus_loss.run_if(in_state(AppState::Playing)),
+					quit_input.run_if(in_state(AppState::Paused)),
+					restart_input.run_if(restart_allowed),
+					game_over_input.run_if(in_state(AppState::GameOver)),
+				),
+			);
+	}
+}
+
+fn load_key_map(mut key_map: ResMut<KeyMap>) {
+	*key_map = KeyMap::load(key_map_path());
+}
+
+/// Each human player steers with their keys, and the n-th connected gamepad steers the n-th
+/// snake with the steering buttons and its left stick. A lone human steers with every key and
+/// gamepad. Gamepads are looked up every frame, so one plugged in mid-game steers right away.
+pub fn snake_movement_input(
+	action_input: ActionInput,
+	players: Res<PlayerCount>,
+	opponents: Res<Opponents>,
+	gamepads: Res<Gamepads>,
+	gamepad_axes: Res<Axis<GamepadAxis>>,
+	// Where each gamepad's stick leaned last frame, a held stick only turns once.
+	mut stick_directions: Local<HashMap<Gamepad, Direction>>,
+	mut snakes: Query<(&Snake, &SnakeHead, &mut DirectionQueue), Without<AiController>>,
+) {
+	let humans = players.humans(&opponents);
+	let steering = |player: usize| if humans == 1 { 0 } else { player };
+	let mut turns = Vec::new();
+	for action in Action::all(MAX_PLAYERS) {
+		if let Action::Steer(player, dir) = action {
+			if action_input.key_just_pressed(action) {
+				turns.push((steering(player), dir));
+			}
+		}
+	}
+	stick_directions.retain(|gamepad, _| gamepads.contains(*gamepad));
+	let mut connected = gamepads.iter().collect::<Vec<Gamepad>>();
+	connected.sort_by_key(|gamepad| gamepad.id);
+	for (index, gamepad) in connected.into_iter().enumerate() {
+		let player = steering(index);
+		for action in Action::all(MAX_PLAYERS) {
+			if let Action::Steer(_, dir) = action {
+				if action_input.button_just_pressed(action, gamepad) {
+					turns.push((player, dir));
+				}
+			}
+		}
+		let stick = stick_direction(
+			gamepad_axes
+				.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+				.unwrap_or_default(),
+			gamepad_axes
+				.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+				.unwrap_or_default(),
+		);
+		match stick {
+			Some(dir) if stick_directions.get(&gamepad) != Some(&dir) => {
+				stick_directions.insert(gamepad, dir);
+				turns.push((player, dir));
+			}
+			Some(_) => {}
+			None => {
+				stick_directions.remove(&gamepad);
+			}
+		}
+	}
+	for (snake, head, mut queue) in snakes.iter_mut() {
+		for (_, dir) in turns.iter().filter(|(player, _)| *player == snake.player) {
+			queue.push(*dir, head.direction);
+		}
+	}
+}
+
+/// The axis the stick leans furthest along, `None` inside the deadzone.
+fn stick_direction(x: f32, y: f32) -> Option<Direction> {
+	if x.abs().max(y.abs()) < STICK_DEADZONE {
+		None
+	} else if x.abs() > y.abs() {
+		Some(if x < 0.0 {
+			Direction::Left
+		} else {
+			Direction::Right
+		})
+	} else {
+		Some(if y < 0.0 {
+			Direction::Down
+		} else {
+			Direction::Up
+		})
+	}
+}
+
+fn log_gamepad_connections(mut connection_reader: EventReader<GamepadConnectionEvent>) {
+	for event in connection_reader.read() {
+		match &event.connection {
+			GamepadConnection::Connected(info) => {
+				info!("Gamepad {} connected: {}", event.gamepad.id, info.name)
+			}
+			GamepadConnection::Disconnected => info!("Gamepad {} disconnected", event.gamepad.id),
+		}
+	}
+}
+
+/// `+` and `-` double and halve the speed of a replay.
+fn playback_speed_input(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut time: ResMut<Time<Virtual>>,
+) {
+	let factor = if keyboard_input.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+		2.0
+	} else if keyboard_input.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+		0.5
+	} else {
+		return;
+	};
+	let speed = (time.relative_speed() * factor).clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED);
+	time.set_relative_speed(speed);
+	info!("Playback speed x{speed}");
+}
+
+fn main_menu_input(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut restart_writer: EventWriter<RestartEvent>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if keyboard_input.just_pressed(KeyCode::Enter) {
+		restart_writer.send(RestartEvent);
+	} else if keyboard_input.just_pressed(KeyCode::KeyC) {
+		next_state.set(AppState::Controls);
+	}
+}
+
+fn restart_input(action_input: ActionInput, mut restart_writer: EventWriter<RestartEvent>) {
+	if action_input.just_pressed(Action::Restart) {
+		restart_writer.send(RestartEvent);
+	}
+}
+
+fn pause_input(
+	action_input: ActionInput,
+	state: Res<State<AppState>>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if action_input.just_pressed(Action::Pause) {
+		match state.get() {
+			AppState::Playing => next_state.set(AppState::Paused),
+			AppState::Paused => next_state.set(AppState::Playing),
+			_ => {}
+		}
+	}
+}
+
+fn pause_on_focus_loss(
+	mut focus_reader: EventReader<WindowFocused>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if focus_reader.read().any(|event| !event.focused) {
+		next_state.set(AppState::Paused);
+	}
+}
+
+fn quit_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut exit_writer: EventWriter<AppExit>) {
+	if keyboard_input.just_pressed(KeyCode::KeyQ) {
+		exit_writer.send(AppExit);
+	}
+}
+
+fn game_over_input(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if keyboard_input.just_pressed(KeyCode::Enter) {
+		next_state.set(AppState::MainMenu);
+	}
+}
@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use bevy::{
+	app::{App, Plugin},
+	ecs::{
+		entity::Entity,
+		event::EventWriter,
+		query::{Or, With},
+		schedule::IntoSystemConfigs,
+		system::{Commands, Query, Res, ResMut, Resource},
+	},
+	prelude::{Deref, DerefMut},
+	render::color::Color,
+	sprite::{Sprite, SpriteBundle},
+	time::{Timer, TimerMode},
+	utils::default,
+};
+use rand::seq::SliceRandom;
+
+use crate::{
+	arena::Arena,
+	components::{Food, Position, Size, Snake, SnakeSegment, Wall},
+	movement::{GameOverCause, GameOverEvent, GrowthEvent},
+	rng::GameRng,
+	score::Score,
+	speed::GameSpeed,
+	tick::{GameTick, TickSet},
+};
+
+const FOOD_COLOR: Color = Color::rgb(1.0, 0.0, 1.0);
+
+const FOOD_POINTS: u32 = 10;
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct FoodSpawnTimer(pub Timer);
+impl Default for FoodSpawnTimer {
+	fn default() -> Self {
+		Self(Timer::from_seconds(1.0, TimerMode::Repeating))
+	}
+}
+
+#[derive(Resource, Clone)]
+pub struct FoodConfig {
+	/// Maximum number of food items on the board at the same time.
+	pub max_items: usize,
+}
+impl Default for FoodConfig {
+	fn default() -> Self {
+		Self { max_items: 3 }
+	}
+}
+
+/// Spawns food on free cells and lets the snake eat it.
+pub struct FoodPlugin;
+impl Plugin for FoodPlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(FoodSpawnTimer::default())
+			.init_resource::<FoodConfig>()
+			.add_systems(
+				GameTick,
+				(
+					snake_eating.in_set(TickSet::Eat),
+					food_spawner.in_set(TickSet::Spawn),
+				),
+			);
+	}
+}
+
+type OccupiedCells<'w, 's> =
+	Query<'w, 's, &'static Position, Or<(With<SnakeSegment>, With<Food>, With<Wall>)>>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn food_spawner(
+	mut commands: Commands,
+	mut food_timer: ResMut<FoodSpawnTimer>,
+	food_config: Res<FoodConfig>,
+	speed: Res<GameSpeed>,
+	arena: Res<Arena>,
+	occupied: OccupiedCells,
+	food: Query<(), With<Food>>,
+	mut rng: ResMut<GameRng>,
+	score: Res<Score>,
+	mut game_over_writer: EventWriter<GameOverEvent>,
+) {
+	let occupied = occupied.iter().copied().collect::<HashSet<Position>>();
+	let free_cells = arena
+		.cells()
+		.filter(|pos| !occupied.contains(pos))
+		.collect::<Vec<Position>>();
+	// Checked every tick, the round is won as soon as the snakes cover the last free cell.
+	if free_cells.is_empty() && food.is_empty() {
+		game_over_writer.send(GameOverEvent::new(GameOverCause::BoardFull, None, &score));
+		return;
+	}
+	// Counted in ticks rather than real time so a stepped simulation spawns
+	// food at the same rate as a windowed one.
+	if !food_timer.tick(speed.interval).just_finished() {
+		return;
+	}
+	if food.iter().count() >= food_config.max_items {
+		return;
+	}
+	let Some(position) = free_cells.choose(&mut **rng) else {
+		return;
+	};
+	commands
+		.spawn(SpriteBundle {
+			sprite: Sprite {
+				color: FOOD_COLOR,
+				..default()
+			},
+			..default()
+		})
+		.insert(Food)
+		.insert(*position)
+		.insert(Size::square(0.8));
+}
+
+pub fn snake_eating(
+	mut commands: Commands,
+	mut growth_writer: EventWriter<GrowthEvent>,
+	mut score: ResMut<Score>,
+	food_positions: Query<(Entity, &Position), With<Food>>,
+	heads: Query<(Entity, &Snake, &Position)>,
+) {
+	for (ent, food_pos) in food_positions.iter() {
+		if let Some((head, snake, _)) = heads.iter().find(|(_, _, head_pos)| *head_pos == food_pos)
+		{
+			commands.entity(ent).despawn();
+			growth_writer.send(GrowthEvent {
+				snake: head,
+				amount: 1,
+			});
+			let player_score = score.player_mut(snake.player);
+			player_score.points += FOOD_POINTS;
+			player_score.food_eaten += 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use bevy::{app::App, ecs::event::Events};
+
+	use super::FoodPlugin;
+	use crate::{
+		arena::Arena,
+		components::SnakeSegment,
+		movement::{GameOverCause, GameOverEvent, GrowthEvent},
+		rng::GameRng,
+		score::Score,
+		speed::{GameSpeed, SpeedConfig},
+		tick::GameTick,
+	};
+
+	#[test]
+	fn a_covered_board_ends_the_round_before_the_spawn_timer_fires() {
+		let arena = Arena {
+			width: 4,
+			height: 4,
+			..Arena::default()
+		};
+		let mut app = App::new();
+		app.add_plugins(FoodPlugin)
+			.add_event::<GrowthEvent>()
+			.add_event::<GameOverEvent>()
+			.insert_resource(arena)
+			.insert_resource(GameSpeed::at_level(&SpeedConfig::default(), 0))
+			.insert_resource(GameRng::new(Some(1)))
+			.init_resource::<Score>();
+		for position in arena.cells() {
+			app.world.spawn((SnakeSegment, position));
+		}
+		app.world.run_schedule(GameTick);
+
+		let causes = app
+			.world
+			.resource::<Events<GameOverEvent>>()
+			.iter_current_update_events()
+			.map(|event| event.cause)
+			.collect::<Vec<GameOverCause>>();
+		assert_eq!(causes, [GameOverCause::BoardFull]);
+	}
+}
@@ -0,0 +1,324 @@
+use bevy::{
+	app::App,
+	ecs::{
+		query::{QueryState, With},
+		schedule::State,
+	},
+};
+
+use crate::{
+	ai::{Board, SnakeView},
+	arena::Arena,
+	components::{Direction, DirectionQueue, Food, Position, Snake, SnakeHead, Wall},
+	headless::headless_app,
+	movement::{GameOverCause, PlayerCount},
+	rng::GameRng,
+	round::{LastGameOver, RestartEvent},
+	score::Score,
+	settings::Settings,
+	tick::{run_game_tick, TickCounter, TickSource},
+	AppState,
+};
+
+/// Planes of [`Observation::grid`], in order.
+pub const GRID_CHANNELS: usize = 5;
+pub const OWN_BODY: usize = 0;
+pub const OWN_HEAD: usize = 1;
+pub const OTHER_SNAKES: usize = 2;
+pub const FOOD: usize = 3;
+pub const WALLS: usize = 4;
+
+/// Length of [`Observation::features`].
+pub const FEATURE_LEN: usize = 11;
+
+/// The board as the agent sees it after a step.
+#[derive(Debug, Clone)]
+pub struct Observation {
+	/// `GRID_CHANNELS x height x width` cells, row-major from the bottom row, 1.0 where the
+	/// agent's body, the agent's head, another snake, food or a wall is.
+	pub grid: Vec<f32>,
+	/// Whether a move straight on, to the left or to the right crashes this tick, the
+	/// heading as up, down, left, right, and whether the closest food is up, down, left or
+	/// right of the head.
+	pub features: Vec<f32>,
+}
+
+/// Reward for each event of a step, summed.
+#[derive(Debug, Clone, Copy)]
+pub struct Rewards {
+	pub food: f32,
+	pub death: f32,
+	/// Filling the board, or outliving every opponent.
+	pub win: f32,
+	/// Every tick survived.
+	pub step: f32,
+	/// Per cell moved towards the closest food, taken away per cell moved away from it.
+	pub approach: f32,
+}
+impl Default for Rewards {
+	fn default() -> Self {
+		Self {
+			food: 1.0,
+			death: -1.0,
+			win: 1.0,
+			step: 0.0,
+			approach: 0.0,
+		}
+	}
+}
+
+/// Where the round stands after a step.
+#[derive(Debug, Clone, Copy)]
+pub struct StepInfo {
+	pub ticks: u64,
+	pub points: u32,
+	pub length: usize,
+	/// How the round ended, once it has.
+	pub cause: Option<GameOverCause>,
+}
+
+/// A gym-style environment over the [`headless_app`]: the agent steers the first snake, one
+/// tick per [`Env::step`], against the `opponents` from the settings.
+///
+/// ```no_run
+/// use block_bite::{
+///     gym::{Env, Rewards},
+///     settings::Settings,
+///     Direction,
+/// };
+///
+/// let mut env = Env::new(Settings::default(), Rewards::default());
+/// let _observation = env.reset(7);
+/// loop {
+///     let (_observation, _reward, done, info) = env.step(Direction::Up);
+///     if done {
+///         println!("{} points in {} ticks", info.points, info.ticks);
+///         break;
+///     }
+/// }
+/// ```
+pub struct Env {
+	app: App,
+	rewards: Rewards,
+	done: bool,
+	snakes: QueryState<(&'static Snake, &'static SnakeHead)>,
+	queues: QueryState<(
+		&'static Snake,
+		&'static SnakeHead,
+		&'static mut DirectionQueue,
+	)>,
+	food: QueryState<&'static Position, With<Food>>,
+	walls: QueryState<&'static Position, With<Wall>>,
+}
+impl Env {
+	/// Replays and the autopilot are left out, the agent is the only human player.
+	pub fn new(mut settings: Settings, rewards: Rewards) -> Self {
+		settings.players = PlayerCount(1);
+		settings.autopilot = false;
+		settings.replay = None;
+		let mut app = headless_app(settings);
+		app.insert_resource(TickSource::External);
+		Self {
+			snakes: app.world.query(),
+			queues: app.world.query(),
+			food: app.world.query_filtered(),
+			walls: app.world.query_filtered(),
+			app,
+			rewards,
+			done: true,
+		}
+	}
+
+	/// Starts a new round played with `seed`.
+	pub fn reset(&mut self, seed: u64) -> Observation {
+		self.app.insert_resource(GameRng::new(Some(seed)));
+		self.app.insert_resource(LastGameOver::default());
+		self.app.world.send_event(RestartEvent);
+		self.app.update();
+		self.done = false;
+		self.observe()
+	}
+
+	/// Turns the agent's snake towards `action`, reversing is ignored like a key press, and
+	/// plays one tick. Once done, steps do nothing until the next [`Env::reset`].
+	pub fn step(&mut self, action: Direction) -> (Observation, f32, bool, StepInfo) {
+		if self.done {
+			return (self.observe(), 0.0, true, self.info());
+		}
+		let before = self.info();
+		let distance_before = self.food_distance();
+		for (snake, head, mut queue) in self.queues.iter_mut(&mut self.app.world) {
+			if snake.player == 0 {
+				queue.push(action, head.direction);
+			}
+		}
+		run_game_tick(&mut self.app.world);
+		// Applies the state change of a round that just ended.
+		self.app.update();
+
+		let info = self.info();
+		let eaten = info.length > before.length;
+		let alive = self.agent().is_some();
+		let ended = *self.app.world.resource::<State<AppState>>().get() != AppState::Playing;
+		let won = ended
+			&& self
+				.app
+				.world
+				.resource::<LastGameOver>()
+				.0
+				.as_ref()
+				.is_some_and(|event| {
+					event.cause == GameOverCause::BoardFull || event.winner == Some(0)
+				});
+		let mut reward = self.rewards.step;
+		if eaten {
+			reward += self.rewards.food;
+		} else if let (Some(before), Some(after)) = (distance_before, self.food_distance()) {
+			reward += self.rewards.approach * (before - after) as f32;
+		}
+		if won {
+			reward += self.rewards.win;
+		} else if ended || !alive {
+			reward += self.rewards.death;
+		}
+		self.done = ended || !alive;
+		(self.observe(), reward, self.done, info)
+	}
+
+	/// Channels, height and width of [`Observation::grid`].
+	pub fn grid_shape(&self) -> [usize; 3] {
+		let arena = self.app.world.resource::<Arena>();
+		[GRID_CHANNELS, arena.height as usize, arena.width as usize]
+	}
+
+	fn info(&self) -> StepInfo {
+		let score = self.app.world.resource::<Score>().player(0);
+		StepInfo {
+			ticks: self.app.world.resource::<TickCounter>().0,
+			points: score.points,
+			length: score.length,
+			cause: self
+				.app
+				.world
+				.resource::<LastGameOver>()
+				.0
+				.as_ref()
+				.map(|event| event.cause),
+		}
+	}
+
+	/// Every snake on the board, with its player, and the board they move on.
+	fn board(&mut self) -> (Vec<(usize, SnakeView)>, Board) {
+		let world = &self.app.world;
+		let arena = *world.resource::<Arena>();
+		let snakes = self
+			.snakes
+			.iter(world)
+			.map(|(snake, head)| {
+				let mut body = snake.body(|segment| world.get::<Position>(segment).copied());
+				// A head that just crashed into a wall is off the board.
+				body.retain(|position| arena.contains(*position));
+				(
+					snake.player,
+					SnakeView {
+						direction: head.direction,
+						body,
+					},
+				)
+			})
+			.filter(|(_, snake)| !snake.body.is_empty())
+			.collect::<Vec<(usize, SnakeView)>>();
+		let board = Board::new(
+			arena,
+			snakes.iter().map(|(_, snake)| snake.body.as_slice()),
+			self.walls.iter(world).copied(),
+			self.food.iter(world).copied().collect(),
+		);
+		(snakes, board)
+	}
+
+	/// The agent's snake, while it's on the board.
+	fn agent(&mut self) -> Option<SnakeView> {
+		let (snakes, _) = self.board();
+		snakes
+			.into_iter()
+			.find_map(|(player, snake)| (player == 0).then_some(snake))
+	}
+
+	fn food_distance(&mut self) -> Option<i32> {
+		let (snakes, board) = self.board();
+		let (_, agent) = snakes.iter().find(|(player, _)| *player == 0)?;
+		board.food_distance(agent.head())
+	}
+
+	fn observe(&mut self) -> Observation {
+		let (snakes, board) = self.board();
+		let (width, height) = (board.arena.width as usize, board.arena.height as usize);
+		let cell = |channel: usize, position: Position| {
+			channel * width * height + position.y as usize * width + position.x as usize
+		};
+		let mut grid = vec![0.0; GRID_CHANNELS * width * height];
+		for (player, snake) in &snakes {
+			for (i, position) in snake.body.iter().enumerate() {
+				let channel = match (*player, i) {
+					(0, 0) => OWN_HEAD,
+					(0, _) => OWN_BODY,
+					_ => OTHER_SNAKES,
+				};
+				grid[cell(channel, *position)] = 1.0;
+			}
+		}
+		for position in self.walls.iter(&self.app.world) {
+			grid[cell(WALLS, *position)] = 1.0;
+		}
+		for position in &board.food {
+			grid[cell(FOOD, *position)] = 1.0;
+		}
+		let features = match snakes.iter().find(|(player, _)| *player == 0) {
+			Some((_, agent)) => features(&board, agent.direction, agent.head()),
+			None => vec![0.0; FEATURE_LEN],
+		};
+		Observation { grid, features }
+	}
+}
+
+fn features(board: &Board, direction: Direction, head: Position) -> Vec<f32> {
+	let (left, right) = match direction {
+		Direction::Up => (Direction::Left, Direction::Right),
+		Direction::Down => (Direction::Right, Direction::Left),
+		Direction::Left => (Direction::Down, Direction::Up),
+		Direction::Right => (Direction::Up, Direction::Down),
+	};
+	let flag = |value: bool| if value { 1.0 } else { 0.0 };
+	let danger = |direction: Direction| {
+		flag(
+			board
+				.step(head, direction)
+				.is_none_or(|next| !board.is_free(next)),
+		)
+	};
+	let (food_up, food_down, food_left, food_right) =
+		board
+			.closest_food(head)
+			.map_or((false, false, false, false), |food| {
+				(
+					food.y > head.y,
+					food.y < head.y,
+					food.x < head.x,
+					food.x > head.x,
+				)
+			});
+	vec![
+		danger(direction),
+		danger(left),
+		danger(right),
+		flag(direction == Direction::Up),
+		flag(direction == Direction::Down),
+		flag(direction == Direction::Left),
+		flag(direction == Direction::Right),
+		flag(food_up),
+		flag(food_down),
+		flag(food_left),
+		flag(food_right),
+	]
+}
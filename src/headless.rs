@@ -0,0 +1,26 @@
+use bevy::{app::App, MinimalPlugins};
+
+use crate::{round::RestartEvent, settings::Settings, tick::TickSource, SimulationPlugin};
+
+/// Builds an app that runs the game rules without a window, input or rendering.
+///
+/// Every `App::update` advances the game by exactly one tick, and the first update starts a
+/// round. Send another [`RestartEvent`] to start over once the state reaches `GameOver`.
+///
+/// ```no_run
+/// use block_bite::{headless::headless_app, settings::Settings};
+///
+/// let mut app = headless_app(Settings::default());
+/// for _ in 0..100 {
+///     app.update();
+/// }
+/// ```
+pub fn headless_app(settings: Settings) -> App {
+	let mut app = App::new();
+	app.add_plugins((MinimalPlugins, SimulationPlugin { settings }))
+		.insert_resource(TickSource::Manual);
+	// Let the initial `MainMenu` state settle first, entering it clears the board.
+	app.update();
+	app.world.send_event(RestartEvent);
+	app
+}
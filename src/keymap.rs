@@ -0,0 +1,430 @@
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+use bevy::{
+	ecs::system::{Res, Resource, SystemParam},
+	input::{
+		gamepad::{Gamepad, GamepadButton, GamepadButtonType, Gamepads},
+		keyboard::KeyCode,
+		ButtonInput,
+	},
+	log::warn,
+};
+
+use crate::{
+	components::Direction,
+	movement::MAX_PLAYERS,
+	settings::{parse_config_lines, write_atomic},
+};
+
+/// Something a player can do with a key or button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+	/// Turn a player's snake.
+	Steer(usize, Direction),
+	Pause,
+	Restart,
+}
+impl Action {
+	/// The steering of the first `players` players, then the shared actions.
+	pub fn all(players: usize) -> impl Iterator<Item = Action> {
+		(0..players)
+			.flat_map(|player| {
+				Direction::ALL
+					.into_iter()
+					.map(move |direction| Action::Steer(player, direction))
+			})
+			.chain([Action::Pause, Action::Restart])
+	}
+
+	/// Key of the action in the key-map file, the first player steers with plain directions.
+	pub fn name(self) -> String {
+		match self {
+			Action::Steer(0, direction) => direction.name().to_string(),
+			Action::Steer(player, direction) => format!("p{}_{}", player + 1, direction.name()),
+			Action::Pause => "pause".to_string(),
+			Action::Restart => "restart".to_string(),
+		}
+	}
+
+	fn from_name(name: &str) -> Option<Self> {
+		Self::all(MAX_PLAYERS).find(|action| action.name() == name)
+	}
+
+	pub fn label(self) -> String {
+		match self {
+			Action::Steer(player, direction) => format!("P{} {direction:?}", player + 1),
+			action => format!("{action:?}"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+	Key(KeyCode),
+	/// The button on any connected gamepad.
+	Button(GamepadButtonType),
+}
+impl Binding {
+	/// `None` for keys and buttons missing from the name tables, those can't be bound.
+	pub fn name(self) -> Option<String> {
+		match self {
+			Binding::Key(key) => key_name(key).map(str::to_string),
+			Binding::Button(button) => button_name(button).map(|name| format!("Pad:{name}")),
+		}
+	}
+
+	pub fn parse(name: &str) -> Option<Self> {
+		match name.strip_prefix("Pad:") {
+			Some(button) => button_from_name(button).map(Binding::Button),
+			None => key_from_name(name).map(Binding::Key),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindError {
+	/// The binding already triggers another action.
+	Conflict(Action),
+	/// The key or button has no name, so it could not be saved.
+	Unnamed,
+}
+
+/// Which keys and buttons trigger which [`Action`], persisted to `path`.
+#[derive(Resource)]
+pub struct KeyMap {
+	bindings: HashMap<Action, Vec<Binding>>,
+	path: Option<PathBuf>,
+}
+impl Default for KeyMap {
+	fn default() -> Self {
+		use Binding::{Button, Key};
+		use Direction::{Down, Left, Right, Up};
+		// Alone, a player steers with every player's keys, see `snake_movement_input`.
+		let bindings = [
+			(
+				Action::Steer(0, Up),
+				vec![Key(KeyCode::ArrowUp), Button(GamepadButtonType::DPadUp)],
+			),
+			(
+				Action::Steer(0, Down),
+				vec![Key(KeyCode::ArrowDown), Button(GamepadButtonType::DPadDown)],
+			),
+			(
+				Action::Steer(0, Left),
+				vec![Key(KeyCode::ArrowLeft), Button(GamepadButtonType::DPadLeft)],
+			),
+			(
+				Action::Steer(0, Right),
+				vec![
+					Key(KeyCode::ArrowRight),
+					Button(GamepadButtonType::DPadRight),
+				],
+			),
+			(Action::Steer(1, Up), vec![Key(KeyCode::KeyW)]),
+			(Action::Steer(1, Down), vec![Key(KeyCode::KeyS)]),
+			(Action::Steer(1, Left), vec![Key(KeyCode::KeyA)]),
+			(Action::Steer(1, Right), vec![Key(KeyCode::KeyD)]),
+			(Action::Steer(2, Up), vec![Key(KeyCode::KeyI)]),
+			(Action::Steer(2, Down), vec![Key(KeyCode::KeyK)]),
+			(Action::Steer(2, Left), vec![Key(KeyCode::KeyJ)]),
+			(Action::Steer(2, Right), vec![Key(KeyCode::KeyL)]),
+			(Action::Steer(3, Up), vec![Key(KeyCode::Numpad8)]),
+			(Action::Steer(3, Down), vec![Key(KeyCode::Numpad5)]),
+			(Action::Steer(3, Left), vec![Key(KeyCode::Numpad4)]),
+			(Action::Steer(3, Right), vec![Key(KeyCode::Numpad6)]),
+			(
+				Action::Pause,
+				vec![
+					Key(KeyCode::Escape),
+					Key(KeyCode::KeyP),
+					Button(GamepadButtonType::Start),
+				],
+			),
+			(Action::Restart, vec![Key(KeyCode::KeyR)]),
+		];
+		Self {
+			bindings: bindings.into_iter().collect(),
+			path: None,
+		}
+	}
+}
+impl KeyMap {
+	/// Starts from the default bindings, actions listed in the file replace theirs.
+	pub fn load(path: Option<PathBuf>) -> Self {
+		let mut key_map = Self::default();
+		match path.as_deref().map(fs::read_to_string) {
+			Some(Ok(contents)) => key_map.apply(&contents),
+			Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+				warn!("Could not read the key-map file: {err}");
+			}
+			_ => {}
+		}
+		key_map.path = path;
+		key_map
+	}
+
+	/// `action = Binding, Binding` lines, `#` starts a comment.
+	fn apply(&mut self, contents: &str) {
+		for line in parse_config_lines(contents) {
+			let (name, bindings) = match line {
+				Ok(pair) => pair,
+				Err(line) => {
+					warn!("Ignoring malformed key-map line: {line}");
+					continue;
+				}
+			};
+			let Some(action) = Action::from_name(name) else {
+				warn!("Ignoring unknown action {name} in the key map");
+				continue;
+			};
+			self.clear(action);
+			for name in bindings
+				.split(',')
+				.map(str::trim)
+				.filter(|name| !name.is_empty())
+			{
+				match Binding::parse(name) {
+					Some(binding) => {
+						if let Err(BindError::Conflict(other)) = self.bind(action, binding) {
+							warn!(
+								"{name} is bound to both {} and {}, keeping {0}",
+								other.label(),
+								action.label()
+							);
+						}
+					}
+					None => warn!("Ignoring unknown key {name} for {}", action.label()),
+				}
+			}
+		}
+	}
+
+	pub fn save(&self) -> io::Result<()> {
+		let Some(path) = &self.path else {
+			return Ok(());
+		};
+		let contents = Action::all(MAX_PLAYERS)
+			.map(|action| {
+				let names = self
+					.bindings(action)
+					.iter()
+					.filter_map(|binding| binding.name())
+					.collect::<Vec<String>>();
+				format!("{} = {}\n", action.name(), names.join(", "))
+			})
+			.collect::<String>();
+		write_atomic(path, &contents)
+	}
+
+	pub fn bindings(&self, action: Action) -> &[Binding] {
+		self.bindings.get(&action).map_or(&[], Vec::as_slice)
+	}
+
+	pub fn action_for(&self, binding: Binding) -> Option<Action> {
+		Action::all(MAX_PLAYERS).find(|action| self.bindings(*action).contains(&binding))
+	}
+
+	/// Adds `binding` to `action`, unless another action already uses it.
+	pub fn bind(&mut self, action: Action, binding: Binding) -> Result<(), BindError> {
+		if binding.name().is_none() {
+			return Err(BindError::Unnamed);
+		}
+		match self.action_for(binding) {
+			Some(other) if other == action => Ok(()),
+			Some(other) => Err(BindError::Conflict(other)),
+			None => {
+				self.bindings.entry(action).or_default().push(binding);
+				Ok(())
+			}
+		}
+	}
+
+	pub fn clear(&mut self, action: Action) {
+		self.bindings.remove(&action);
+	}
+}
+
+pub fn key_map_path() -> Option<PathBuf> {
+	dirs::config_dir().map(|dir| dir.join("block_bite").join("keys.txt"))
+}
+
+/// Reads [`Action`]s through the [`KeyMap`].
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+	key_map: Res<'w, KeyMap>,
+	keyboard: Res<'w, ButtonInput<KeyCode>>,
+	gamepads: Res<'w, Gamepads>,
+	gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+}
+impl ActionInput<'_> {
+	/// Pressed with any of its keys, or its buttons on any gamepad.
+	pub fn just_pressed(&self, action: Action) -> bool {
+		self.key_just_pressed(action)
+			|| self
+				.gamepads
+				.iter()
+				.any(|gamepad| self.button_just_pressed(action, gamepad))
+	}
+
+	pub fn key_just_pressed(&self, action: Action) -> bool {
+		self.key_map
+			.bindings(action)
+			.iter()
+			.any(|binding| matches!(*binding, Binding::Key(key) if self.keyboard.just_pressed(key)))
+	}
+
+	pub fn button_just_pressed(&self, action: Action, gamepad: Gamepad) -> bool {
+		self.key_map.bindings(action).iter().any(|binding| {
+			matches!(*binding, Binding::Button(button)
+				if self.gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button)))
+		})
+	}
+}
+
+/// Names for the variants of `$ty` listed, spelled like the variants themselves.
+macro_rules! name_table {
+	($to_name:ident, $from_name:ident, $ty:ident { $($variant:ident),* $(,)? }) => {
+		fn $to_name(value: $ty) -> Option<&'static str> {
+			match value {
+				$($ty::$variant => Some(stringify!($variant)),)*
+				_ => None,
+			}
+		}
+
+		fn $from_name(name: &str) -> Option<$ty> {
+			match name {
+				$(stringify!($variant) => Some($ty::$variant),)*
+				_ => None,
+			}
+		}
+	};
+}
+
+name_table!(
+	key_name,
+	key_from_name,
+	KeyCode {
+		KeyA,
+		KeyB,
+		KeyC,
+		KeyD,
+		KeyE,
+		KeyF,
+		KeyG,
+		KeyH,
+		KeyI,
+		KeyJ,
+		KeyK,
+		KeyL,
+		KeyM,
+		KeyN,
+		KeyO,
+		KeyP,
+		KeyQ,
+		KeyR,
+		KeyS,
+		KeyT,
+		KeyU,
+		KeyV,
+		KeyW,
+		KeyX,
+		KeyY,
+		KeyZ,
+		Digit0,
+		Digit1,
+		Digit2,
+		Digit3,
+		Digit4,
+		Digit5,
+		Digit6,
+		Digit7,
+		Digit8,
+		Digit9,
+		Numpad0,
+		Numpad1,
+		Numpad2,
+		Numpad3,
+		Numpad4,
+		Numpad5,
+		Numpad6,
+		Numpad7,
+		Numpad8,
+		Numpad9,
+		NumpadAdd,
+		NumpadSubtract,
+		NumpadMultiply,
+		NumpadDivide,
+		NumpadDecimal,
+		NumpadEnter,
+		ArrowUp,
+		ArrowDown,
+		ArrowLeft,
+		ArrowRight,
+		Escape,
+		Enter,
+		Space,
+		Tab,
+		Backspace,
+		Delete,
+		Insert,
+		Home,
+		End,
+		PageUp,
+		PageDown,
+		ShiftLeft,
+		ShiftRight,
+		ControlLeft,
+		ControlRight,
+		AltLeft,
+		AltRight,
+		Minus,
+		Equal,
+		BracketLeft,
+		BracketRight,
+		Backslash,
+		Semicolon,
+		Quote,
+		Backquote,
+		Comma,
+		Period,
+		Slash,
+		F1,
+		F2,
+		F3,
+		F4,
+		F5,
+		F6,
+		F7,
+		F8,
+		F9,
+		F10,
+		F11,
+		F12,
+	}
+);
+
+name_table!(
+	button_name,
+	button_from_name,
+	GamepadButtonType {
+		South,
+		East,
+		North,
+		West,
+		C,
+		Z,
+		LeftTrigger,
+		LeftTrigger2,
+		RightTrigger,
+		RightTrigger2,
+		Select,
+		Start,
+		Mode,
+		LeftThumb,
+		RightThumb,
+		DPadUp,
+		DPadDown,
+		DPadLeft,
+		DPadRight,
+	}
+);
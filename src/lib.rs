@@ -0,0 +1,117 @@
+//! Block Bite, a snake game for Bevy.
+//!
+//! Add [`BlockBitePlugin`] to an app that already has Bevy's `DefaultPlugins` to get the whole
+//! game, or pick the sub-plugins it is made of to embed only parts of it. [`SimulationPlugin`]
+//! alone runs the rules without a window, see [`headless::headless_app`].
+
+use bevy::{
+	app::{App, Plugin},
+	ecs::schedule::States,
+	log::warn,
+};
+
+pub mod ai;
+pub mod arena;
+pub mod autopilot;
+pub mod components;
+pub mod controls;
+pub mod food;
+pub mod gym;
+pub mod headless;
+pub mod keymap;
+pub mod movement;
+pub mod rendering;
+pub mod replay;
+pub mod rng;
+pub mod round;
+pub mod score;
+pub mod settings;
+pub mod speed;
+pub mod tick;
+pub mod ui;
+
+pub use components::{
+	Direction, DirectionQueue, Food, Position, Size, Snake, SnakeHead, SnakeSegment, Wall,
+};
+
+use ai::AiPlugin;
+use autopilot::Autopilot;
+use controls::ControlsPlugin;
+use food::FoodPlugin;
+use movement::{MovementPlugin, MAX_PLAYERS};
+use rendering::RenderingPlugin;
+use replay::ReplayPlugin;
+use rng::GameRng;
+use round::RoundPlugin;
+use score::{HighScorePlugin, ScorePlugin};
+use settings::Settings;
+use speed::SpeedPlugin;
+use tick::TickPlugin;
+use ui::UiPlugin;
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+	#[default]
+	MainMenu,
+	Playing,
+	Paused,
+	/// A finished round made the high-score table, the player types a name.
+	EnterName,
+	GameOver,
+	/// The rebinding screen, reached from the main menu.
+	Controls,
+}
+
+/// The game rules and round flow, without input, rendering or UI.
+#[derive(Default)]
+pub struct SimulationPlugin {
+	pub settings: Settings,
+}
+impl Plugin for SimulationPlugin {
+	fn build(&self, app: &mut App) {
+		let mut settings = self.settings.clone();
+		if settings.fit_players() {
+			warn!("At most {MAX_PLAYERS} snakes fit in a round, dropping opponents");
+		}
+		app.insert_resource(settings.arena)
+			.insert_resource(settings.walls.clone())
+			.insert_resource(settings.food.clone())
+			.insert_resource(settings.speed.clone())
+			.insert_resource(settings.player_count())
+			.insert_resource(settings.opponents.clone())
+			.insert_resource(Autopilot(settings.autopilot))
+			.insert_resource(GameRng::new(settings.seed))
+			.init_state::<AppState>()
+			.add_plugins((
+				TickPlugin,
+				AiPlugin,
+				MovementPlugin,
+				FoodPlugin,
+				RoundPlugin,
+				ScorePlugin,
+				SpeedPlugin,
+			));
+	}
+}
+
+/// The whole game: rules, replays, controls, rendering and UI.
+#[derive(Default)]
+pub struct BlockBitePlugin {
+	pub settings: Settings,
+}
+impl Plugin for BlockBitePlugin {
+	fn build(&self, app: &mut App) {
+		app.add_plugins((
+			SimulationPlugin {
+				settings: self.settings.clone(),
+			},
+			HighScorePlugin,
+			ReplayPlugin {
+				playback: self.settings.replay.clone(),
+			},
+			ControlsPlugin,
+			RenderingPlugin,
+			UiPlugin,
+		));
+	}
+}
@@ -0,0 +1,480 @@
+use bevy::{
+	app::{App, Plugin},
+	ecs::{
+		entity::Entity,
+		event::{Event, EventReader, EventWriter},
+		query::With,
+		schedule::IntoSystemConfigs,
+		system::{Commands, Query, Res, ResMut, Resource},
+	},
+	render::color::Color,
+	sprite::{Sprite, SpriteBundle},
+	utils::default,
+};
+
+use crate::{
+	ai::Opponents,
+	arena::{Arena, BoundaryMode},
+	components::{Direction, DirectionQueue, Position, Size, Snake, SnakeHead, SnakeSegment, Wall},
+	score::Score,
+	tick::{GameTick, TickSet},
+};
+
+pub const MAX_PLAYERS: usize = 4;
+
+/// Head and segment colors, by player.
+const SNAKE_COLORS: [(Color, Color); MAX_PLAYERS] = [
+	(Color::rgb(0.7, 0.7, 0.7), Color::rgb(0.3, 0.3, 0.3)),
+	(Color::rgb(0.5, 0.9, 0.5), Color::rgb(0.2, 0.5, 0.2)),
+	(Color::rgb(0.5, 0.7, 1.0), Color::rgb(0.2, 0.3, 0.6)),
+	(Color::rgb(1.0, 0.7, 0.4), Color::rgb(0.6, 0.35, 0.1)),
+];
+
+/// Heading of a freshly spawned snake.
+pub const START_DIRECTION: Direction = Direction::Up;
+
+/// Segments of a freshly spawned snake, head included.
+pub const START_LENGTH: usize = 2;
+
+/// Number of snakes in a round, one per player.
+#[derive(Resource, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerCount(pub usize);
+impl Default for PlayerCount {
+	fn default() -> Self {
+		Self(1)
+	}
+}
+impl PlayerCount {
+	/// Players left to humans, the [`Opponents`] take the last slots.
+	pub fn humans(self, opponents: &Opponents) -> usize {
+		self.0.saturating_sub(opponents.0.len())
+	}
+}
+
+#[derive(Event)]
+pub struct GrowthEvent {
+	/// Head entity of the snake that grows.
+	pub snake: Entity,
+	/// Number of segments to append to the tail.
+	pub amount: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverCause {
+	Wall,
+	Tail,
+	/// Ran into another snake.
+	Snake,
+	/// The snakes cover every free cell, the round is won.
+	BoardFull,
+}
+
+/// A snake crashed and is out of the round.
+#[derive(Event, Clone, Copy)]
+pub struct CrashEvent {
+	pub player: usize,
+	pub cause: GameOverCause,
+}
+
+/// The round is over.
+#[derive(Event, Clone)]
+pub struct GameOverEvent {
+	/// How the round ended, the last crash in a multi-player round.
+	pub cause: GameOverCause,
+	/// The last snake standing in a multi-player round, `None` for a single player or a draw.
+	pub winner: Option<usize>,
+	/// Final length of every player's snake, by player.
+	pub lengths: Vec<usize>,
+}
+impl GameOverEvent {
+	pub fn new(cause: GameOverCause, winner: Option<usize>, score: &Score) -> Self {
+		Self {
+			cause,
+			winner,
+			lengths: score.players.iter().map(|player| player.length).collect(),
+		}
+	}
+
+	pub fn length(&self, player: usize) -> usize {
+		self.lengths.get(player).copied().unwrap_or_default()
+	}
+}
+
+/// Moves, grows and collides the snakes.
+pub struct MovementPlugin;
+impl Plugin for MovementPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<PlayerCount>()
+			.init_resource::<Opponents>()
+			.add_event::<GrowthEvent>()
+			.add_event::<CrashEvent>()
+			.add_event::<GameOverEvent>()
+			.add_systems(
+				GameTick,
+				(
+					snake_movement.in_set(TickSet::Move),
+					snake_collision.in_set(TickSet::Collide),
+					snake_growth.in_set(TickSet::Grow),
+				),
+			);
+	}
+}
+
+/// Cells `player`'s snake starts on, head first. The snakes of a round start side by side
+/// across the arena.
+pub fn start_cells(arena: &Arena, player: usize, players: usize) -> Vec<Position> {
+	let x = (arena.width as usize * (2 * player + 1) / (2 * players)) as i32;
+	let y = arena.height as i32 / 2;
+	(0..START_LENGTH)
+		.map(|offset| Position {
+			x,
+			y: y - offset as i32,
+		})
+		.collect()
+}
+
+/// Spawns `player`'s snake on its [`start_cells`].
+pub fn spawn_snake(
+	commands: &mut Commands,
+	arena: &Arena,
+	player: usize,
+	players: usize,
+) -> Entity {
+	let cells = start_cells(arena, player, players);
+	let head = commands
+		.spawn(SpriteBundle {
+			sprite: Sprite {
+				color: SNAKE_COLORS[player].0,
+				..default()
+			},
+			..default()
+		})
+		.insert(SnakeHead {
+			direction: START_DIRECTION,
+		})
+		.insert(DirectionQueue::default())
+		.insert(SnakeSegment)
+		.insert(cells[0])
+		.insert(Size::square(0.8))
+		.id();
+	let mut segments = vec![head];
+	for position in &cells[1..] {
+		segments.push(spawn_segment(commands, player, *position));
+	}
+	commands.entity(head).insert(Snake {
+		player,
+		segments,
+		last_tail_position: None,
+	});
+	head
+}
+
+pub fn spawn_segment(commands: &mut Commands, player: usize, position: Position) -> Entity {
+	commands
+		.spawn(SpriteBundle {
+			sprite: Sprite {
+				color: SNAKE_COLORS[player].1,
+				..default()
+			},
+			..default()
+		})
+		.insert(SnakeSegment)
+		.insert(position)
+		.insert(Size::square(0.65))
+		.id()
+}
+
+pub fn snake_movement(
+	arena: Res<Arena>,
+	mut snakes: Query<(Entity, &mut Snake, &mut SnakeHead, &mut DirectionQueue)>,
+	mut positions: Query<&mut Position>,
+) {
+	for (head_entity, mut snake, mut head, mut queue) in snakes.iter_mut() {
+		if let Some(dir) = queue.pop() {
+			if dir != head.direction.oppsite() {
+				head.direction = dir;
+			}
+		}
+		let segment_positions = snake
+			.segments
+			.iter()
+			.map(|e| *positions.get(*e).unwrap())
+			.collect::<Vec<Position>>();
+		let mut head_pos = positions.get_mut(head_entity).unwrap();
+		match &head.direction {
+			Direction::Left => {
+				head_pos.x -= 1;
+			}
+			Direction::Right => {
+				head_pos.x += 1;
+			}
+			Direction::Up => {
+				head_pos.y += 1;
+			}
+			Direction::Down => {
+				head_pos.y -= 1;
+			}
+		};
+		if arena.boundary == BoundaryMode::Wrap {
+			*head_pos = arena.wrap(*head_pos);
+		}
+		segment_positions
+			.iter()
+			.zip(snake.segments.iter().skip(1))
+			.for_each(|(pos, segment)| {
+				*positions.get_mut(*segment).unwrap() = *pos;
+			});
+		snake.last_tail_position = segment_positions.last().copied();
+	}
+}
+
+/// Takes crashed snakes out of the round and ends it once no snake, in a multi-player
+/// round at most one, or no human's snake is left.
+#[allow(clippy::too_many_arguments)]
+pub fn snake_collision(
+	mut commands: Commands,
+	arena: Res<Arena>,
+	players: Res<PlayerCount>,
+	opponents: Res<Opponents>,
+	snakes: Query<(Entity, &Snake)>,
+	positions: Query<&Position>,
+	walls: Query<&Position, With<Wall>>,
+	score: Res<Score>,
+	mut crash_writer: EventWriter<CrashEvent>,
+	mut game_over_writer: EventWriter<GameOverEvent>,
+) {
+	let mut crashes = Vec::new();
+	for (head_entity, snake) in snakes.iter() {
+		let head_pos = *positions.get(head_entity).unwrap();
+		let hits = |segments: &[Entity]| {
+			segments
+				.iter()
+				.any(|e| positions.get(*e).is_ok_and(|pos| *pos == head_pos))
+		};
+		let cause = if !arena.contains(head_pos) || walls.iter().any(|wall| *wall == head_pos) {
+			Some(GameOverCause::Wall)
+		} else if hits(&snake.segments[1..]) {
+			Some(GameOverCause::Tail)
+		} else if snakes
+			.iter()
+			.any(|(other_entity, other)| other_entity != head_entity && hits(&other.segments))
+		{
+			Some(GameOverCause::Snake)
+		} else {
+			None
+		};
+		if let Some(cause) = cause {
+			crashes.push((snake, cause));
+		}
+	}
+	let Some(&(_, last_cause)) = crashes.last() else {
+		return;
+	};
+	for (snake, cause) in &crashes {
+		crash_writer.send(CrashEvent {
+			player: snake.player,
+			cause: *cause,
+		});
+	}
+	let survivors = snakes
+		.iter()
+		.map(|(_, snake)| snake.player)
+		.filter(|player| crashes.iter().all(|(crashed, _)| crashed.player != *player))
+		.collect::<Vec<usize>>();
+	// Computer players don't get to finish a round on their own, it could go on forever.
+	let humans = players.humans(&opponents);
+	let humans_left = humans == 0 || survivors.iter().any(|player| *player < humans);
+	if survivors.is_empty() || (players.0 > 1 && survivors.len() == 1) || !humans_left {
+		let winner = match survivors[..] {
+			[winner] => Some(winner),
+			_ => None,
+		};
+		game_over_writer.send(GameOverEvent::new(last_cause, winner, &score));
+	} else {
+		// The others play on, without the crashed snakes in their way.
+		for (snake, _) in &crashes {
+			for segment in &snake.segments {
+				commands.entity(*segment).despawn();
+			}
+		}
+	}
+}
+
+pub fn snake_growth(
+	mut commands: Commands,
+	mut snakes: Query<&mut Snake>,
+	mut score: ResMut<Score>,
+	mut growth_reader: EventReader<GrowthEvent>,
+) {
+	for event in growth_reader.read() {
+		let Ok(mut snake) = snakes.get_mut(event.snake) else {
+			continue;
+		};
+		// Food never spawns on a snake, so something has always moved before
+		// the first growth and left a tail position behind.
+		let Some(tail_position) = snake.last_tail_position else {
+			continue;
+		};
+		for _ in 0..event.amount {
+			let segment = spawn_segment(&mut commands, snake.player, tail_position);
+			snake.segments.push(segment);
+		}
+		score.player_mut(snake.player).length = snake.segments.len();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use bevy::{
+		app::App,
+		ecs::{
+			entity::Entity,
+			event::Events,
+			schedule::{IntoSystemConfigs, IntoSystemSetConfigs},
+		},
+	};
+
+	use super::{CrashEvent, GameOverCause, GameOverEvent, MovementPlugin, PlayerCount};
+	use crate::{
+		ai::{Opponents, StrategyKind},
+		arena::{Arena, BoundaryMode},
+		components::{Direction, DirectionQueue, Food, Position, Snake, SnakeHead, SnakeSegment},
+		food::snake_eating,
+		score::Score,
+		tick::{GameTick, TickSet},
+	};
+
+	fn test_app(arena: Arena) -> App {
+		let mut app = App::new();
+		app.add_plugins(MovementPlugin)
+			// `TickPlugin` orders the stages in the game.
+			.configure_sets(
+				GameTick,
+				(TickSet::Move, TickSet::Collide, TickSet::Eat, TickSet::Grow).chain(),
+			)
+			.add_systems(GameTick, snake_eating.in_set(TickSet::Eat))
+			.insert_resource(arena)
+			.init_resource::<Score>();
+		app
+	}
+
+	/// A two-cell snake for `player` with its head on `head`, heading away from its tail.
+	fn spawn_snake_at(
+		app: &mut App,
+		player: usize,
+		head: Position,
+		tail: Position,
+		direction: Direction,
+	) -> Entity {
+		let tail = app.world.spawn((SnakeSegment, tail)).id();
+		let head_entity = app
+			.world
+			.spawn((
+				SnakeSegment,
+				head,
+				SnakeHead { direction },
+				DirectionQueue::default(),
+			))
+			.id();
+		app.world.entity_mut(head_entity).insert(Snake {
+			player,
+			segments: vec![head_entity, tail],
+			last_tail_position: None,
+		});
+		head_entity
+	}
+
+	#[test]
+	fn growth_adds_a_segment_per_food() {
+		let mut app = test_app(Arena::default());
+		let head = spawn_snake_at(
+			&mut app,
+			0,
+			Position { x: 5, y: 5 },
+			Position { x: 5, y: 4 },
+			Direction::Up,
+		);
+		for y in [6, 7, 9] {
+			app.world.spawn((Food, Position { x: 5, y }));
+		}
+		for _ in 0..4 {
+			app.world.run_schedule(GameTick);
+		}
+
+		let segments = app.world.get::<Snake>(head).unwrap().segments.len();
+		assert_eq!(segments, 2 + 3);
+		let score = app.world.resource::<Score>().player(0);
+		assert_eq!(score.food_eaten, 3);
+		assert_eq!(score.length, segments);
+	}
+
+	#[test]
+	fn wrapping_heads_come_back_on_the_opposite_edge() {
+		let edges = [
+			((0, 5), (1, 5), Direction::Left, (9, 5)),
+			((9, 5), (8, 5), Direction::Right, (0, 5)),
+			((5, 9), (5, 8), Direction::Up, (5, 0)),
+			((5, 0), (5, 1), Direction::Down, (5, 9)),
+		];
+		for ((x, y), (tail_x, tail_y), direction, (wrapped_x, wrapped_y)) in edges {
+			let mut app = test_app(Arena {
+				boundary: BoundaryMode::Wrap,
+				..Arena::default()
+			});
+			let head = spawn_snake_at(
+				&mut app,
+				0,
+				Position { x, y },
+				Position {
+					x: tail_x,
+					y: tail_y,
+				},
+				direction,
+			);
+			app.world.run_schedule(GameTick);
+
+			assert!(
+				*app.world.get::<Position>(head).unwrap()
+					== Position {
+						x: wrapped_x,
+						y: wrapped_y
+					},
+				"{direction:?} off the edge"
+			);
+			assert!(
+				app.world.resource::<Events<CrashEvent>>().is_empty(),
+				"{direction:?} off the edge crashed"
+			);
+		}
+	}
+
+	#[test]
+	fn the_round_ends_when_the_last_human_crashes() {
+		let mut app = test_app(Arena::default());
+		app.insert_resource(PlayerCount(3))
+			.insert_resource(Opponents(vec![StrategyKind::TailChaser; 2]));
+		spawn_snake_at(
+			&mut app,
+			0,
+			Position { x: 5, y: 9 },
+			Position { x: 5, y: 8 },
+			Direction::Up,
+		);
+		for (player, x) in [(1, 1), (2, 8)] {
+			spawn_snake_at(
+				&mut app,
+				player,
+				Position { x, y: 5 },
+				Position { x, y: 4 },
+				Direction::Up,
+			);
+		}
+		app.world.run_schedule(GameTick);
+
+		let events = app.world.resource::<Events<GameOverEvent>>();
+		let game_overs = events.iter_current_update_events().collect::<Vec<_>>();
+		assert_eq!(game_overs.len(), 1);
+		assert_eq!(game_overs[0].cause, GameOverCause::Wall);
+		assert_eq!(game_overs[0].winner, None);
+	}
+}
@@ -0,0 +1,161 @@
+use bevy::{
+	app::{App, Plugin, PostUpdate, Startup},
+	core_pipeline::{core_2d::Camera2dBundle, tonemapping::Tonemapping},
+	ecs::{
+		change_detection::{DetectChanges, Ref},
+		component::Component,
+		event::EventReader,
+		query::With,
+		schedule::IntoSystemConfigs,
+		system::{Commands, Query, Res, ResMut, Resource},
+	},
+	math::{Vec2, Vec3},
+	render::{
+		camera::{Camera, ClearColor},
+		color::Color,
+	},
+	sprite::{Sprite, SpriteBundle},
+	transform::components::Transform,
+	utils::default,
+	window::{PrimaryWindow, Window, WindowResized},
+};
+
+use crate::{
+	arena::Arena,
+	components::{Position, Size},
+};
+
+const CLEAR_COLOR: Color = Color::rgb(0.04, 0.04, 0.04);
+const ARENA_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
+
+pub const HUD_HEIGHT: f32 = 30.0;
+
+/// Size of a cell on screen and where the arena is centered, so cells stay square and the
+/// arena is letterboxed inside whatever shape the window has. Leaves room for the HUD on top.
+#[derive(Default, Resource)]
+pub struct ArenaLayout {
+	pub tile_size: f32,
+	pub offset: Vec2,
+}
+impl ArenaLayout {
+	pub fn fit(arena: &Arena, window_width: f32, window_height: f32) -> Self {
+		let available_height = (window_height - HUD_HEIGHT).max(0.0);
+		Self {
+			tile_size: (window_width / arena.width as f32)
+				.min(available_height / arena.height as f32),
+			offset: Vec2::new(0.0, -HUD_HEIGHT / 2.0),
+		}
+	}
+}
+
+#[derive(Component)]
+pub struct ArenaBackground;
+
+/// Window size that shows `arena` at its configured cell size.
+pub fn window_resolution(arena: &Arena) -> (f32, f32) {
+	(
+		arena.width as f32 * arena.cell_size,
+		arena.height as f32 * arena.cell_size + HUD_HEIGHT,
+	)
+}
+
+/// Draws the arena and maps grid `Position`s and `Size`s onto the window.
+pub struct RenderingPlugin;
+impl Plugin for RenderingPlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(ClearColor(CLEAR_COLOR))
+			.insert_resource(ArenaLayout::default())
+			.add_systems(Startup, (setup_camera, spawn_arena_background))
+			.add_systems(
+				PostUpdate,
+				(update_arena_layout, (position_translation, size_scaling)).chain(),
+			);
+	}
+}
+
+fn setup_camera(mut commands: Commands) {
+	commands.spawn((Camera2dBundle {
+		camera: Camera {
+			hdr: true,
+			..default()
+		},
+		tonemapping: Tonemapping::TonyMcMapface,
+		..default()
+	},));
+}
+
+fn spawn_arena_background(mut commands: Commands, arena: Res<Arena>) {
+	commands
+		.spawn(SpriteBundle {
+			sprite: Sprite {
+				color: ARENA_COLOR,
+				..default()
+			},
+			transform: Transform::from_xyz(0.0, -HUD_HEIGHT / 2.0, -1.0),
+			..default()
+		})
+		.insert(ArenaBackground)
+		.insert(Size {
+			width: arena.width as f32,
+			height: arena.height as f32,
+		});
+}
+
+pub fn update_arena_layout(
+	arena: Res<Arena>,
+	mut layout: ResMut<ArenaLayout>,
+	mut resize_reader: EventReader<WindowResized>,
+	primary_query: Query<&Window, With<PrimaryWindow>>,
+) {
+	if resize_reader.read().last().is_none() && !arena.is_changed() {
+		return;
+	}
+	let Ok(window) = primary_query.get_single() else {
+		return;
+	};
+	*layout = ArenaLayout::fit(&arena, window.width(), window.height());
+}
+
+pub fn size_scaling(layout: Res<ArenaLayout>, mut q: Query<(Ref<Size>, &mut Transform)>) {
+	for (sprite_size, mut transform) in q.iter_mut() {
+		if !layout.is_changed() && !sprite_size.is_changed() {
+			continue;
+		}
+		transform.scale = Vec3::new(
+			sprite_size.width * layout.tile_size,
+			sprite_size.height * layout.tile_size,
+			1.0,
+		)
+	}
+}
+
+pub fn position_translation(
+	arena: Res<Arena>,
+	layout: Res<ArenaLayout>,
+	mut q: Query<(Ref<Position>, &mut Transform)>,
+) {
+	fn convert(pos: f32, bound_game: f32, tile_size: f32, offset: f32) -> f32 {
+		offset + (pos - (bound_game - 1.) / 2.) * tile_size
+	}
+
+	for (pos, mut transform) in q.iter_mut() {
+		if !layout.is_changed() && !pos.is_changed() {
+			continue;
+		}
+		transform.translation = Vec3::new(
+			convert(
+				pos.x as f32,
+				arena.width as f32,
+				layout.tile_size,
+				layout.offset.x,
+			),
+			convert(
+				pos.y as f32,
+				arena.height as f32,
+				layout.tile_size,
+				layout.offset.y,
+			),
+			0.0,
+		);
+	}
+}
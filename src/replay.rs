@@ -0,0 +1,331 @@
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::{Path, PathBuf},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+	app::{App, Plugin, PreUpdate},
+	ecs::{
+		event::EventReader,
+		schedule::IntoSystemConfigs,
+		system::{Query, Res, ResMut, Resource},
+	},
+	log::{info, warn},
+};
+
+use crate::{
+	ai::Opponents,
+	arena::{Arena, BoundaryMode, WallLayout},
+	components::{Direction, DirectionQueue, Snake, SnakeHead},
+	food::FoodConfig,
+	movement::{snake_movement, GameOverEvent, PlayerCount, START_DIRECTION},
+	rng::GameRng,
+	round::{game_over, restart_round, RestartEvent},
+	settings::{parse_config_lines, write_atomic, Settings},
+	speed::SpeedConfig,
+	tick::{GameTick, TickCounter, TickSet},
+};
+
+/// Saved replays kept in the data directory, the oldest are removed past it.
+const MAX_REPLAYS: usize = 50;
+
+/// A snake changing direction.
+#[derive(Clone, Copy)]
+pub struct Turn {
+	/// Tick the turn was taken on.
+	pub tick: u64,
+	pub player: usize,
+	pub direction: Direction,
+}
+
+/// Everything needed to play a round again: the rules it ran under, its seed and every turn
+/// the snakes took.
+#[derive(Default, Clone)]
+pub struct Replay {
+	pub seed: u64,
+	pub arena: Arena,
+	pub walls: WallLayout,
+	pub food: FoodConfig,
+	pub speed: SpeedConfig,
+	pub players: PlayerCount,
+	pub turns: Vec<Turn>,
+}
+impl Replay {
+	pub fn load(path: &Path) -> io::Result<Self> {
+		Self::parse(&fs::read_to_string(path)?)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed replay file"))
+	}
+
+	/// Config-style `key = value` lines for the rules plus one `turn = TICK PLAYER DIRECTION`
+	/// line per turn, `None` if a turn is malformed or the seed is missing. Turns without a
+	/// player, from single-player replays, belong to the first.
+	fn parse(contents: &str) -> Option<Self> {
+		let mut settings = Settings::default();
+		let mut turns = Vec::new();
+		for line in parse_config_lines(contents) {
+			let (key, value) = line.ok()?;
+			match key {
+				"turn" => {
+					let fields = value.split_whitespace().collect::<Vec<&str>>();
+					let (tick, player, direction) = match fields[..] {
+						[tick, direction] => (tick, "0", direction),
+						[tick, player, direction] => (tick, player, direction),
+						_ => return None,
+					};
+					turns.push(Turn {
+						tick: tick.parse().ok()?,
+						player: player.parse().ok()?,
+						direction: Direction::from_name(direction)?,
+					});
+				}
+				key => settings.set(key, value),
+			}
+		}
+		turns.sort_by_key(|turn| turn.tick);
+		Some(Self {
+			seed: settings.seed?,
+			arena: settings.arena,
+			walls: settings.walls,
+			food: settings.food,
+			speed: settings.speed,
+			players: settings.players,
+			turns,
+		})
+	}
+
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let mut contents = format!(
+			"# Block Bite replay\nseed = {}\narena_width = {}\narena_height = {}\nboundary = {}\nmax_food = {}\n\
+			 start_interval_ms = {}\nmin_interval_ms = {}\nspeedup = {}\nfood_per_level = {}\n\
+			 players = {}\n",
+			self.seed,
+			self.arena.width,
+			self.arena.height,
+			match self.arena.boundary {
+				BoundaryMode::Walls => "walls",
+				BoundaryMode::Wrap => "wrap",
+			},
+			self.food.max_items,
+			self.speed.start_interval.as_millis(),
+			self.speed.min_interval.as_millis(),
+			self.speed.speedup,
+			self.speed.food_per_level,
+			self.players.0,
+		);
+		if !self.walls.0.is_empty() {
+			let cells = self
+				.walls
+				.0
+				.iter()
+				.map(|position| format!("{},{}", position.x, position.y))
+				.collect::<Vec<String>>();
+			contents.push_str(&format!("walls = {}\n", cells.join(" ")));
+		}
+		for turn in &self.turns {
+			contents.push_str(&format!(
+				"turn = {} {} {}\n",
+				turn.tick,
+				turn.player,
+				turn.direction.name()
+			));
+		}
+		write_atomic(path, &contents)
+	}
+
+	/// Makes `settings` play by the replay's rules, keeping what only affects the looks.
+	pub fn apply_to(&self, settings: &mut Settings) {
+		settings.arena = Arena {
+			cell_size: settings.arena.cell_size,
+			..self.arena
+		};
+		settings.walls = self.walls.clone();
+		settings.food = self.food.clone();
+		settings.speed = self.speed.clone();
+		// Every snake, computer-controlled or not, follows the recorded turns.
+		settings.players = self.players;
+		settings.opponents = Opponents::default();
+		settings.autopilot = false;
+		settings.seed = Some(self.seed);
+	}
+}
+
+fn replay_dir() -> Option<PathBuf> {
+	dirs::data_dir().map(|dir| dir.join("block_bite").join("replays"))
+}
+
+/// A file in `dir` named after the current time in milliseconds, numbered when rounds end
+/// within the same one.
+fn replay_path(dir: &Path) -> PathBuf {
+	let millis = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |since| since.as_millis());
+	(0..)
+		.map(|n| match n {
+			0 => dir.join(format!("{millis}.txt")),
+			_ => dir.join(format!("{millis}-{n}.txt")),
+		})
+		.find(|path| !path.exists())
+		.unwrap()
+}
+
+/// Removes the oldest replays in `dir` until at most `MAX_REPLAYS` are left.
+fn prune_replays(dir: &Path) -> io::Result<()> {
+	let mut replays = fs::read_dir(dir)?
+		.filter_map(Result::ok)
+		.filter(|entry| entry.path().extension().is_some_and(|ext| ext == "txt"))
+		.map(|entry| Ok((entry.metadata()?.modified()?, entry.path())))
+		.collect::<io::Result<Vec<(SystemTime, PathBuf)>>>()?;
+	if replays.len() <= MAX_REPLAYS {
+		return Ok(());
+	}
+	replays.sort();
+	for (_, path) in &replays[..replays.len() - MAX_REPLAYS] {
+		fs::remove_file(path)?;
+	}
+	Ok(())
+}
+
+/// The round being recorded.
+#[derive(Default, Resource)]
+pub struct ReplayRecorder {
+	replay: Replay,
+	/// Direction of each player's last recorded turn, `START_DIRECTION` before the first.
+	headings: HashMap<usize, Direction>,
+}
+
+/// The replay being played back, steering the snakes in place of the players.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+	pub replay: Replay,
+	/// Index of the next turn to feed to a snake.
+	next_turn: usize,
+}
+
+/// Records every round and saves it to the data directory when it ends, or, given a
+/// `playback`, replays that one instead.
+#[derive(Default)]
+pub struct ReplayPlugin {
+	pub playback: Option<Replay>,
+}
+impl Plugin for ReplayPlugin {
+	fn build(&self, app: &mut App) {
+		match &self.playback {
+			Some(replay) => {
+				app.insert_resource(ReplayPlayback {
+					replay: replay.clone(),
+					next_turn: 0,
+				})
+				.add_systems(PreUpdate, rewind_playback.after(restart_round))
+				.add_systems(GameTick, play_turns.in_set(TickSet::Input));
+			}
+			None => {
+				app.init_resource::<ReplayRecorder>()
+					.add_systems(PreUpdate, start_recording.after(restart_round))
+					.add_systems(
+						GameTick,
+						(
+							record_turns.in_set(TickSet::Move).after(snake_movement),
+							save_replay.in_set(TickSet::End).after(game_over),
+						),
+					);
+			}
+		}
+	}
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start_recording(
+	mut restart_reader: EventReader<RestartEvent>,
+	mut recorder: ResMut<ReplayRecorder>,
+	rng: Res<GameRng>,
+	arena: Res<Arena>,
+	walls: Res<WallLayout>,
+	food_config: Res<FoodConfig>,
+	speed_config: Res<SpeedConfig>,
+	players: Res<PlayerCount>,
+) {
+	if restart_reader.read().last().is_none() {
+		return;
+	}
+	*recorder = ReplayRecorder {
+		replay: Replay {
+			seed: rng.seed(),
+			arena: *arena,
+			walls: walls.clone(),
+			food: food_config.clone(),
+			speed: speed_config.clone(),
+			players: *players,
+			turns: Vec::new(),
+		},
+		headings: HashMap::new(),
+	};
+}
+
+fn record_turns(
+	mut recorder: ResMut<ReplayRecorder>,
+	tick_counter: Res<TickCounter>,
+	snakes: Query<(&Snake, &SnakeHead)>,
+) {
+	for (snake, head) in snakes.iter() {
+		let heading = recorder
+			.headings
+			.insert(snake.player, head.direction)
+			.unwrap_or(START_DIRECTION);
+		if head.direction != heading {
+			recorder.replay.turns.push(Turn {
+				tick: tick_counter.0,
+				player: snake.player,
+				direction: head.direction,
+			});
+		}
+	}
+}
+
+fn save_replay(mut game_over_reader: EventReader<GameOverEvent>, recorder: Res<ReplayRecorder>) {
+	if game_over_reader.read().last().is_none() {
+		return;
+	}
+	let Some(dir) = replay_dir() else {
+		return;
+	};
+	let path = replay_path(&dir);
+	match recorder.replay.save(&path) {
+		Ok(()) => info!("Saved replay to {}", path.display()),
+		Err(err) => warn!("Could not save the replay: {err}"),
+	}
+	if let Err(err) = prune_replays(&dir) {
+		warn!("Could not remove old replays: {err}");
+	}
+}
+
+fn rewind_playback(
+	mut restart_reader: EventReader<RestartEvent>,
+	mut playback: ResMut<ReplayPlayback>,
+) {
+	if restart_reader.read().last().is_some() {
+		playback.next_turn = 0;
+	}
+}
+
+/// Queues the recorded turns due this tick, so `snake_movement` takes them exactly as it
+/// took the player's.
+fn play_turns(
+	mut playback: ResMut<ReplayPlayback>,
+	tick_counter: Res<TickCounter>,
+	mut snakes: Query<(&Snake, &SnakeHead, &mut DirectionQueue)>,
+) {
+	while let Some(&turn) = playback.replay.turns.get(playback.next_turn) {
+		if turn.tick > tick_counter.0 {
+			break;
+		}
+		if let Some((_, head, mut queue)) = snakes
+			.iter_mut()
+			.find(|(snake, ..)| snake.player == turn.player)
+		{
+			queue.push(turn.direction, head.direction);
+		}
+		playback.next_turn += 1;
+	}
+}
@@ -0,0 +1,42 @@
+use bevy::{
+	ecs::system::Resource,
+	log::info,
+	prelude::{Deref, DerefMut},
+};
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+/// The one source of randomness for the game rules, so the same seed and the same inputs
+/// play out the same round.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng {
+	/// Seed from the settings, `None` picks a fresh one every round.
+	fixed_seed: Option<u64>,
+	seed: u64,
+	#[deref]
+	rng: StdRng,
+}
+impl GameRng {
+	pub fn new(fixed_seed: Option<u64>) -> Self {
+		let seed = fixed_seed.unwrap_or_else(random_seed);
+		Self {
+			fixed_seed,
+			seed,
+			rng: StdRng::seed_from_u64(seed),
+		}
+	}
+
+	/// Seed of the current round.
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	/// Reseeds for a new round, with the configured seed or a fresh random one.
+	pub fn start_round(&mut self) {
+		*self = Self::new(self.fixed_seed);
+		info!("Round seed {}", self.seed);
+	}
+}
+
+fn random_seed() -> u64 {
+	thread_rng().gen()
+}
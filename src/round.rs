@@ -0,0 +1,172 @@
+use bevy::{
+	app::{App, Plugin, PreUpdate},
+	ecs::{
+		entity::Entity,
+		event::{Event, EventReader},
+		query::{Or, With},
+		schedule::{IntoSystemConfigs, NextState, OnEnter, State},
+		system::{Commands, Query, Res, ResMut, Resource},
+	},
+	log::info,
+	render::color::Color,
+	sprite::{Sprite, SpriteBundle},
+	utils::default,
+};
+
+use crate::{
+	arena::{Arena, WallLayout},
+	autopilot::Autopilot,
+	components::{Food, Position, Size, SnakeSegment, Wall},
+	food::FoodSpawnTimer,
+	movement::{spawn_snake, start_cells, GameOverEvent, PlayerCount, START_LENGTH},
+	replay::ReplayPlayback,
+	rng::GameRng,
+	score::{HighScores, PlayerScore, Score},
+	tick::{GameTick, TickCounter, TickSet},
+	AppState,
+};
+
+/// Starts a fresh round, from any state.
+#[derive(Default, Event)]
+pub struct RestartEvent;
+
+#[derive(Default, Resource)]
+pub struct LastGameOver(pub Option<GameOverEvent>);
+
+const WALL_COLOR: Color = Color::rgb(0.45, 0.35, 0.3);
+
+type RoundEntities<'w, 's> =
+	Query<'w, 's, Entity, Or<(With<SnakeSegment>, With<Food>, With<Wall>)>>;
+
+/// Starts, ends and tears down rounds.
+pub struct RoundPlugin;
+impl Plugin for RoundPlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(LastGameOver::default())
+			.add_event::<RestartEvent>()
+			.add_systems(OnEnter(AppState::MainMenu), despawn_round)
+			// Restarting in `PreUpdate` so the respawned snake is on the board before
+			// the movement systems look up its segments.
+			.add_systems(PreUpdate, (restart_round, spawn_walls.after(restart_round)))
+			.add_systems(GameTick, game_over.in_set(TickSet::End));
+	}
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn game_over(
+	mut game_over_reader: EventReader<GameOverEvent>,
+	mut last_game_over: ResMut<LastGameOver>,
+	score: Res<Score>,
+	high_scores: Option<Res<HighScores>>,
+	playback: Option<Res<ReplayPlayback>>,
+	players: Res<PlayerCount>,
+	autopilot: Res<Autopilot>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if let Some(event) = game_over_reader.read().last() {
+		match event.winner {
+			Some(winner) => info!("Game over ({:?}), player {} wins", event.cause, winner + 1),
+			None => info!("Game over ({:?})", event.cause),
+		}
+		last_game_over.0 = Some(event.clone());
+		// Without a high-score table (headless runs) there is nobody to ask for a name, a
+		// replayed or self-steered round doesn't count and the table is for single-player
+		// rounds.
+		if playback.is_none()
+			&& !autopilot.0
+			&& players.0 == 1
+			&& high_scores.is_some_and(|high_scores| high_scores.qualifies(score.player(0).points))
+		{
+			next_state.set(AppState::EnterName);
+		} else {
+			next_state.set(AppState::GameOver);
+		}
+	}
+}
+
+fn clear_round(commands: &mut Commands, entities: &RoundEntities) {
+	for entity in entities.iter() {
+		commands.entity(entity).despawn();
+	}
+}
+
+pub fn despawn_round(mut commands: Commands, entities: RoundEntities) {
+	clear_round(&mut commands, &entities);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn restart_round(
+	mut commands: Commands,
+	mut restart_reader: EventReader<RestartEvent>,
+	entities: RoundEntities,
+	mut food_timer: ResMut<FoodSpawnTimer>,
+	mut score: ResMut<Score>,
+	mut rng: ResMut<GameRng>,
+	mut tick_counter: ResMut<TickCounter>,
+	arena: Res<Arena>,
+	players: Res<PlayerCount>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if restart_reader.read().last().is_none() {
+		return;
+	}
+	clear_round(&mut commands, &entities);
+	food_timer.reset();
+	rng.start_round();
+	*tick_counter = TickCounter::default();
+	for player in 0..players.0 {
+		spawn_snake(&mut commands, &arena, player, players.0);
+	}
+	*score = Score {
+		players: vec![
+			PlayerScore {
+				length: START_LENGTH,
+				..default()
+			};
+			players.0
+		],
+		..default()
+	};
+	next_state.set(AppState::Playing);
+}
+
+/// Builds the [`WallLayout`] for a new round, leaving out cells off the board or under a
+/// starting snake.
+fn spawn_walls(
+	mut commands: Commands,
+	mut restart_reader: EventReader<RestartEvent>,
+	layout: Res<WallLayout>,
+	arena: Res<Arena>,
+	players: Res<PlayerCount>,
+) {
+	if restart_reader.read().last().is_none() {
+		return;
+	}
+	let snakes = (0..players.0)
+		.flat_map(|player| start_cells(&arena, player, players.0))
+		.collect::<Vec<Position>>();
+	for position in layout
+		.0
+		.iter()
+		.filter(|position| arena.contains(**position) && !snakes.contains(position))
+	{
+		commands
+			.spawn(SpriteBundle {
+				sprite: Sprite {
+					color: WALL_COLOR,
+					..default()
+				},
+				..default()
+			})
+			.insert(Wall)
+			.insert(*position)
+			.insert(Size::square(1.0));
+	}
+}
+
+pub fn restart_allowed(state: Res<State<AppState>>) -> bool {
+	matches!(
+		state.get(),
+		AppState::Playing | AppState::Paused | AppState::GameOver
+	)
+}
@@ -0,0 +1,157 @@
+use std::{cmp::Reverse, fs, io, path::PathBuf, time::Duration};
+
+use bevy::{
+	app::{App, Plugin, Startup, Update},
+	ecs::{
+		schedule::{common_conditions::in_state, IntoSystemConfigs},
+		system::{Res, ResMut, Resource},
+	},
+	log::warn,
+	time::Time,
+};
+
+use crate::{settings::write_atomic, AppState};
+
+const HIGH_SCORE_COUNT: usize = 10;
+
+#[derive(Default, Clone, Copy)]
+pub struct PlayerScore {
+	pub points: u32,
+	pub food_eaten: u32,
+	pub length: usize,
+}
+
+#[derive(Default, Resource)]
+pub struct Score {
+	/// Indexed by player.
+	pub players: Vec<PlayerScore>,
+	/// Time spent in `AppState::Playing` this round.
+	pub elapsed: Duration,
+}
+impl Score {
+	pub fn player(&self, player: usize) -> PlayerScore {
+		self.players.get(player).copied().unwrap_or_default()
+	}
+
+	pub fn player_mut(&mut self, player: usize) -> &mut PlayerScore {
+		if self.players.len() <= player {
+			self.players.resize(player + 1, PlayerScore::default());
+		}
+		&mut self.players[player]
+	}
+
+	/// Food eaten by all players together.
+	pub fn food_eaten(&self) -> u32 {
+		self.players.iter().map(|player| player.food_eaten).sum()
+	}
+}
+
+#[derive(Clone)]
+pub struct HighScoreEntry {
+	pub name: String,
+	pub points: u32,
+	pub length: usize,
+}
+
+/// Top `HIGH_SCORE_COUNT` rounds, best first, persisted to `path`.
+#[derive(Default, Resource)]
+pub struct HighScores {
+	pub entries: Vec<HighScoreEntry>,
+	path: Option<PathBuf>,
+}
+impl HighScores {
+	pub fn load(path: Option<PathBuf>) -> Self {
+		let entries = match path.as_deref().map(fs::read_to_string) {
+			Some(Ok(contents)) => Self::parse(&contents).unwrap_or_else(|| {
+				warn!("High-score file is corrupt, starting with an empty table");
+				Vec::new()
+			}),
+			Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+				warn!("Could not read the high-score file: {err}");
+				Vec::new()
+			}
+			_ => Vec::new(),
+		};
+		Self { entries, path }
+	}
+
+	/// One `points<TAB>length<TAB>name` line per entry, `None` if any line is malformed.
+	fn parse(contents: &str) -> Option<Vec<HighScoreEntry>> {
+		let mut entries = contents
+			.lines()
+			.filter(|line| !line.trim().is_empty())
+			.map(|line| {
+				let mut fields = line.splitn(3, '\t');
+				Some(HighScoreEntry {
+					points: fields.next()?.parse().ok()?,
+					length: fields.next()?.parse().ok()?,
+					name: fields.next()?.to_string(),
+				})
+			})
+			.collect::<Option<Vec<HighScoreEntry>>>()?;
+		entries.sort_by_key(|entry| Reverse(entry.points));
+		entries.truncate(HIGH_SCORE_COUNT);
+		Some(entries)
+	}
+
+	pub fn save(&self) -> io::Result<()> {
+		let Some(path) = &self.path else {
+			return Ok(());
+		};
+		let contents = self
+			.entries
+			.iter()
+			.map(|entry| format!("{}\t{}\t{}\n", entry.points, entry.length, entry.name))
+			.collect::<String>();
+		write_atomic(path, &contents)
+	}
+
+	pub fn qualifies(&self, points: u32) -> bool {
+		points > 0
+			&& (self.entries.len() < HIGH_SCORE_COUNT
+				|| self
+					.entries
+					.last()
+					.is_none_or(|entry| points > entry.points))
+	}
+
+	pub fn insert(&mut self, entry: HighScoreEntry) {
+		let index = self
+			.entries
+			.iter()
+			.position(|existing| entry.points > existing.points)
+			.unwrap_or(self.entries.len());
+		self.entries.insert(index, entry);
+		self.entries.truncate(HIGH_SCORE_COUNT);
+	}
+}
+
+fn high_score_path() -> Option<PathBuf> {
+	dirs::data_dir().map(|dir| dir.join("block_bite").join("highscores.txt"))
+}
+
+/// Keeps the round's score.
+pub struct ScorePlugin;
+impl Plugin for ScorePlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(Score::default())
+			.add_systems(Update, tick_score_time.run_if(in_state(AppState::Playing)));
+	}
+}
+
+/// Loads and saves the high-score table in the user's data directory.
+pub struct HighScorePlugin;
+impl Plugin for HighScorePlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(HighScores::default())
+			.add_systems(Startup, load_high_scores);
+	}
+}
+
+fn load_high_scores(mut high_scores: ResMut<HighScores>) {
+	*high_scores = HighScores::load(high_score_path());
+}
+
+pub fn tick_score_time(time: Res<Time>, mut score: ResMut<Score>) {
+	score.elapsed += time.delta();
+}
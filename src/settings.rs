@@ -0,0 +1,220 @@
+use std::{
+	env, fs, io,
+	path::{Path, PathBuf},
+	time::Duration,
+};
+
+use crate::{
+	ai::{Opponents, StrategyKind},
+	arena::{Arena, BoundaryMode, WallLayout, MIN_ARENA_SIZE},
+	autopilot::HamiltonianCycle,
+	components::Position,
+	food::FoodConfig,
+	movement::{PlayerCount, MAX_PLAYERS},
+	replay::Replay,
+	speed::SpeedConfig,
+};
+
+/// Start-up configuration, read from the config file and overridden by command-line flags.
+#[derive(Default, Clone)]
+pub struct Settings {
+	pub arena: Arena,
+	pub walls: WallLayout,
+	pub food: FoodConfig,
+	pub speed: SpeedConfig,
+	/// Human players.
+	pub players: PlayerCount,
+	/// Computer players, after the humans. Together they make at most `MAX_PLAYERS` snakes.
+	pub opponents: Opponents,
+	/// The first player's snake steers itself around the board, from `--autopilot`.
+	pub autopilot: bool,
+	/// Seed for [`GameRng`](crate::rng::GameRng), random every round when unset.
+	pub seed: Option<u64>,
+	/// Round to play back instead of letting the player steer, from `--replay FILE`.
+	pub replay: Option<Replay>,
+}
+impl Settings {
+	pub fn load() -> Self {
+		let args = env::args().skip(1).collect::<Vec<String>>();
+		let mut settings = Self::default();
+		let explicit_path = args
+			.iter()
+			.position(|arg| arg == "--config")
+			.and_then(|index| args.get(index + 1))
+			.map(PathBuf::from);
+		let is_explicit = explicit_path.is_some();
+		if let Some(path) = explicit_path.or_else(config_path) {
+			match fs::read_to_string(&path) {
+				Ok(contents) => settings.apply_config(&contents),
+				Err(err) if is_explicit || err.kind() != io::ErrorKind::NotFound => {
+					eprintln!("Could not read config file {}: {err}", path.display());
+				}
+				Err(_) => {}
+			}
+		}
+		settings.apply_args(&args);
+		// A replay only plays back right under the rules it was recorded with.
+		if let Some(replay) = settings.replay.clone() {
+			replay.apply_to(&mut settings);
+		}
+		if settings.autopilot && HamiltonianCycle::new(&settings.arena, &settings.walls).is_none() {
+			eprintln!("The autopilot needs an even number of cells and no walls, steer yourself");
+			settings.autopilot = false;
+		}
+		settings
+	}
+
+	/// Keeps the humans within `1..=MAX_PLAYERS` and drops the opponents that don't fit next
+	/// to them. `true` when any were dropped.
+	pub fn fit_players(&mut self) -> bool {
+		self.players.0 = self.players.0.clamp(1, MAX_PLAYERS);
+		let max_opponents = MAX_PLAYERS - self.players.0;
+		let dropped = self.opponents.0.len() > max_opponents;
+		self.opponents.0.truncate(max_opponents);
+		dropped
+	}
+
+	/// Snakes in a round, humans and opponents.
+	pub fn player_count(&self) -> PlayerCount {
+		PlayerCount(self.players.0 + self.opponents.0.len())
+	}
+
+	fn apply_config(&mut self, contents: &str) {
+		for line in parse_config_lines(contents) {
+			match line {
+				Ok((key, value)) => self.set(key, value),
+				Err(line) => eprintln!("Ignoring malformed config line: {line}"),
+			}
+		}
+	}
+
+	fn apply_args(&mut self, args: &[String]) {
+		let mut args = args.iter().map(String::as_str);
+		while let Some(arg) = args.next() {
+			match arg {
+				"--config" => {
+					args.next();
+				}
+				"--arena" => match args.next().and_then(|value| value.split_once('x')) {
+					Some((width, height)) => {
+						self.set("arena_width", width);
+						self.set("arena_height", height);
+					}
+					None => eprintln!("--arena expects WIDTHxHEIGHT"),
+				},
+				"--cell-size" => self.set("cell_size", args.next().unwrap_or_default()),
+				"--max-food" => self.set("max_food", args.next().unwrap_or_default()),
+				"--walls" => self.set("walls", args.next().unwrap_or_default()),
+				"--boundary" => self.set("boundary", args.next().unwrap_or_default()),
+				"--autopilot" => self.set("autopilot", "true"),
+				"--opponents" => self.set("opponents", args.next().unwrap_or_default()),
+				"--players" => self.set("players", args.next().unwrap_or_default()),
+				"--seed" => self.set("seed", args.next().unwrap_or_default()),
+				"--replay" => match args.next() {
+					Some(path) => match Replay::load(Path::new(path)) {
+						Ok(replay) => self.replay = Some(replay),
+						Err(err) => eprintln!("Could not read replay file {path}: {err}"),
+					},
+					None => eprintln!("--replay expects a file"),
+				},
+				_ => eprintln!("Ignoring unknown argument {arg}"),
+			}
+		}
+	}
+
+	pub(crate) fn set(&mut self, key: &str, value: &str) {
+		let valid = match key {
+			"arena_width" => parse_at_least(value, MIN_ARENA_SIZE).map(|v| self.arena.width = v),
+			"arena_height" => parse_at_least(value, MIN_ARENA_SIZE).map(|v| self.arena.height = v),
+			"cell_size" => value
+				.parse::<f32>()
+				.ok()
+				.filter(|v| *v > 0.0)
+				.map(|v| self.arena.cell_size = v),
+			"max_food" => parse_at_least(value, 1).map(|v| self.food.max_items = v as usize),
+			"boundary" => match value {
+				"walls" => Some(BoundaryMode::Walls),
+				"wrap" => Some(BoundaryMode::Wrap),
+				_ => None,
+			}
+			.map(|v| self.arena.boundary = v),
+			// `X,Y` cells separated by spaces, every `walls` line adds to the layout.
+			"walls" => value
+				.split_whitespace()
+				.map(parse_position)
+				.collect::<Option<Vec<Position>>>()
+				.map(|v| self.walls.0.extend(v)),
+			"start_interval_ms" => parse_at_least(value, 1)
+				.map(|v| self.speed.start_interval = Duration::from_millis(v.into())),
+			"min_interval_ms" => parse_at_least(value, 1)
+				.map(|v| self.speed.min_interval = Duration::from_millis(v.into())),
+			"speedup" => value
+				.parse::<f32>()
+				.ok()
+				.filter(|v| *v > 0.0 && *v <= 1.0)
+				.map(|v| self.speed.speedup = v),
+			"food_per_level" => parse_at_least(value, 1).map(|v| self.speed.food_per_level = v),
+			"players" => parse_at_least(value, 1)
+				.filter(|v| *v as usize <= MAX_PLAYERS)
+				.map(|v| self.players = PlayerCount(v as usize)),
+			"opponents" => value
+				.split(',')
+				.map(str::trim)
+				.filter(|name| !name.is_empty())
+				.map(StrategyKind::parse)
+				.collect::<Option<Vec<StrategyKind>>>()
+				.map(|v| self.opponents = Opponents(v)),
+			"autopilot" => value.parse::<bool>().ok().map(|v| self.autopilot = v),
+			"seed" => value.parse::<u64>().ok().map(|v| self.seed = Some(v)),
+			_ => {
+				eprintln!("Ignoring unknown setting {key}");
+				return;
+			}
+		};
+		if valid.is_none() {
+			eprintln!("Ignoring invalid value {value:?} for {key}");
+		}
+	}
+}
+
+/// The trimmed `key = value` pairs of `contents`, skipping blank lines and `#` comments.
+/// Lines without an `=` come back as errors.
+pub(crate) fn parse_config_lines(
+	contents: &str,
+) -> impl Iterator<Item = Result<(&str, &str), &str>> {
+	contents
+		.lines()
+		.map(|line| line.split('#').next().unwrap_or_default().trim())
+		.filter(|line| !line.is_empty())
+		.map(|line| {
+			line.split_once('=')
+				.map(|(key, value)| (key.trim(), value.trim()))
+				.ok_or(line)
+		})
+}
+
+/// Writes to a temporary file first so a crash never leaves a half-written file behind.
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let tmp_path = path.with_extension("tmp");
+	fs::write(&tmp_path, contents)?;
+	fs::rename(&tmp_path, path)
+}
+
+fn parse_at_least(value: &str, min: u32) -> Option<u32> {
+	value.parse::<u32>().ok().filter(|v| *v >= min)
+}
+
+fn parse_position(value: &str) -> Option<Position> {
+	let (x, y) = value.split_once(',')?;
+	Some(Position {
+		x: x.trim().parse().ok()?,
+		y: y.trim().parse().ok()?,
+	})
+}
+
+fn config_path() -> Option<PathBuf> {
+	dirs::config_dir().map(|dir| dir.join("block_bite").join("config.txt"))
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use bevy::{
+	app::{App, Plugin, PreUpdate},
+	ecs::{
+		event::EventReader,
+		schedule::IntoSystemConfigs,
+		system::{Res, ResMut, Resource},
+	},
+	time::{Fixed, Time},
+};
+
+use crate::{
+	round::{restart_round, RestartEvent},
+	score::Score,
+	tick::{GameTick, TickSet},
+};
+
+/// How the tick interval shrinks as the snake eats.
+#[derive(Resource, Clone)]
+pub struct SpeedConfig {
+	/// Tick interval at level 0.
+	pub start_interval: Duration,
+	/// The interval never drops below this.
+	pub min_interval: Duration,
+	/// Each level multiplies the interval by this factor, in `(0, 1]`.
+	pub speedup: f32,
+	/// Food to eat per level, 1 speeds up on every bite.
+	pub food_per_level: u32,
+}
+impl Default for SpeedConfig {
+	fn default() -> Self {
+		Self {
+			start_interval: Duration::from_millis(150),
+			min_interval: Duration::from_millis(60),
+			speedup: 0.9,
+			food_per_level: 5,
+		}
+	}
+}
+impl SpeedConfig {
+	pub fn interval(&self, level: u32) -> Duration {
+		self.start_interval
+			.mul_f32(self.speedup.powi(level as i32))
+			.max(self.min_interval)
+	}
+}
+
+/// Speed level of the current round and the tick interval it plays at.
+#[derive(Resource, Clone, Copy)]
+pub struct GameSpeed {
+	pub level: u32,
+	pub interval: Duration,
+}
+impl GameSpeed {
+	pub fn at_level(config: &SpeedConfig, level: u32) -> Self {
+		Self {
+			level,
+			interval: config.interval(level),
+		}
+	}
+}
+impl Default for GameSpeed {
+	fn default() -> Self {
+		Self::at_level(&SpeedConfig::default(), 0)
+	}
+}
+
+/// Speeds the game up as the snake eats.
+pub struct SpeedPlugin;
+impl Plugin for SpeedPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<SpeedConfig>()
+			.init_resource::<GameSpeed>()
+			.add_systems(PreUpdate, reset_game_speed.after(restart_round))
+			.add_systems(GameTick, update_game_speed.in_set(TickSet::Grow));
+	}
+}
+
+fn reset_game_speed(
+	mut restart_reader: EventReader<RestartEvent>,
+	config: Res<SpeedConfig>,
+	mut speed: ResMut<GameSpeed>,
+	mut fixed_time: ResMut<Time<Fixed>>,
+) {
+	if restart_reader.read().last().is_some() {
+		*speed = GameSpeed::at_level(&config, 0);
+		fixed_time.set_timestep(speed.interval);
+	}
+}
+
+pub fn update_game_speed(
+	config: Res<SpeedConfig>,
+	score: Res<Score>,
+	mut speed: ResMut<GameSpeed>,
+	mut fixed_time: ResMut<Time<Fixed>>,
+) {
+	let level = score.food_eaten() / config.food_per_level;
+	if level != speed.level {
+		*speed = GameSpeed::at_level(&config, level);
+		fixed_time.set_timestep(speed.interval);
+	}
+}
@@ -0,0 +1,110 @@
+use bevy::{
+	app::{App, FixedUpdate, Plugin, Update},
+	ecs::{
+		schedule::{
+			common_conditions::{in_state, resource_equals},
+			IntoSystemConfigs, IntoSystemSetConfigs, NextState, OnEnter, OnExit, ScheduleLabel,
+			SystemSet,
+		},
+		system::{ResMut, Resource},
+		world::World,
+	},
+	time::{Time, Virtual},
+};
+
+use crate::AppState;
+
+/// One step of the game rules, run in [`TickSet`] order.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameTick;
+
+/// The stages of a [`GameTick`], in the order they run.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TickSet {
+	/// Feed buffered turns to the snake.
+	Input,
+	Move,
+	/// Detect collisions.
+	Collide,
+	Eat,
+	Grow,
+	/// Spawn new food.
+	Spawn,
+	/// End the round once every stage had its say, a full board is only found on spawning.
+	End,
+}
+
+/// What advances [`GameTick`].
+#[derive(Resource, Default, PartialEq)]
+pub enum TickSource {
+	/// Once per [`GameSpeed`](crate::speed::GameSpeed) interval in `FixedUpdate`, catching
+	/// up on slow frames so the game plays the same at any frame rate.
+	#[default]
+	Fixed,
+	/// Once per `App::update`, for headless runs stepped by the caller.
+	Manual,
+	/// Only when the caller runs [`run_game_tick`], so it can look at the board between a
+	/// round starting and its first tick, as [`Env`](crate::gym::Env) does.
+	External,
+}
+
+/// Number of ticks run since the round started, the index of the running tick during
+/// [`GameTick`].
+#[derive(Default, Resource)]
+pub struct TickCounter(pub u64);
+
+/// Runs [`GameTick`] while a round is being played.
+pub struct TickPlugin;
+impl Plugin for TickPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_schedule(GameTick)
+			.configure_sets(
+				GameTick,
+				(
+					TickSet::Input,
+					TickSet::Move,
+					TickSet::Collide,
+					TickSet::Eat,
+					TickSet::Grow,
+					TickSet::Spawn,
+					TickSet::End,
+				)
+					.chain(),
+			)
+			.init_resource::<TickSource>()
+			.init_resource::<TickCounter>()
+			.add_systems(OnEnter(AppState::Paused), freeze_time)
+			.add_systems(OnExit(AppState::Paused), unfreeze_time)
+			.add_systems(
+				FixedUpdate,
+				run_game_tick
+					.run_if(in_state(AppState::Playing))
+					.run_if(resource_equals(TickSource::Fixed)),
+			)
+			.add_systems(
+				Update,
+				run_game_tick
+					.run_if(in_state(AppState::Playing))
+					.run_if(resource_equals(TickSource::Manual)),
+			);
+	}
+}
+
+pub fn run_game_tick(world: &mut World) {
+	// State changes only apply after `FixedUpdate`, so a slow frame could otherwise keep
+	// ticking a round that already ended or was paused.
+	if world.resource::<NextState<AppState>>().0.is_some() {
+		return;
+	}
+	world.run_schedule(GameTick);
+	world.resource_mut::<TickCounter>().0 += 1;
+}
+
+/// Stops game time while paused, so the tick and food timers resume where they left off.
+fn freeze_time(mut time: ResMut<Time<Virtual>>) {
+	time.pause();
+}
+
+fn unfreeze_time(mut time: ResMut<Time<Virtual>>) {
+	time.unpause();
+}
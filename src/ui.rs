@@ -0,0 +1,533 @@
+use bevy::{
+	app::{App, Plugin, Update},
+	ecs::{
+		change_detection::{DetectChanges, Ref},
+		component::Component,
+		entity::Entity,
+		event::EventReader,
+		query::With,
+		schedule::{
+			common_conditions::in_state, IntoSystemConfigs, NextState, OnEnter, OnExit,
+			OnTransition,
+		},
+		system::{Commands, Query, Res, ResMut, Resource},
+	},
+	hierarchy::{BuildChildren, DespawnRecursiveExt},
+	input::{gamepad::GamepadButton, keyboard::KeyCode, ButtonInput},
+	log::warn,
+	render::color::Color,
+	text::{Text, TextStyle},
+	ui::{
+		node_bundles::{NodeBundle, TextBundle},
+		AlignItems, BackgroundColor, FlexDirection, JustifyContent, PositionType, Style, UiRect,
+		Val,
+	},
+	utils::default,
+	window::ReceivedCharacter,
+};
+
+use crate::{
+	keymap::{Action, BindError, Binding, KeyMap},
+	movement::{GameOverCause, PlayerCount, MAX_PLAYERS},
+	rendering::HUD_HEIGHT,
+	replay::ReplayPlayback,
+	rng::GameRng,
+	round::LastGameOver,
+	score::{HighScoreEntry, HighScores, Score},
+	speed::GameSpeed,
+	AppState,
+};
+
+const UI_TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+const HUD_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+/// Laid over the arena while paused.
+const PAUSE_DIM_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.5);
+
+const HIGH_SCORE_NAME_LEN: usize = 12;
+
+#[derive(Default, Resource)]
+pub struct NameEntry(pub String);
+
+#[derive(Component)]
+pub struct MainMenuUi;
+
+#[derive(Component)]
+pub struct PausedUi;
+
+#[derive(Component)]
+pub struct GameOverUi;
+
+#[derive(Component)]
+pub struct NameEntryUi;
+
+#[derive(Component)]
+pub struct NameEntryText;
+
+/// Selection on the rebinding screen.
+#[derive(Default, Resource)]
+pub struct Rebinding {
+	/// Index into `Action::all`, every player's actions are listed since a lone player steers
+	/// with all of them.
+	pub selected: usize,
+	/// The next key or button pressed is bound to the selected action.
+	pub listening: bool,
+	pub message: String,
+}
+
+#[derive(Component)]
+pub struct RebindUi;
+
+#[derive(Component)]
+pub struct RebindText;
+
+#[derive(Component)]
+pub struct HudUi;
+
+#[derive(Component)]
+pub struct HudText;
+
+/// Menus, overlays, high-score name entry, the rebinding screen and the in-game HUD.
+pub struct UiPlugin;
+impl Plugin for UiPlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(NameEntry::default())
+			.insert_resource(Rebinding::default())
+			.add_systems(
+				OnEnter(AppState::MainMenu),
+				(despawn_with::<HudUi>, spawn_main_menu),
+			)
+			.add_systems(OnExit(AppState::MainMenu), despawn_with::<MainMenuUi>)
+			.add_systems(
+				OnTransition {
+					from: AppState::MainMenu,
+					to: AppState::Playing,
+				},
+				spawn_hud,
+			)
+			.add_systems(OnEnter(AppState::Controls), spawn_rebind_ui)
+			.add_systems(
+				OnExit(AppState::Controls),
+				(despawn_with::<RebindUi>, save_key_map),
+			)
+			.add_systems(OnEnter(AppState::Paused), spawn_paused_ui)
+			.add_systems(OnExit(AppState::Paused), despawn_with::<PausedUi>)
+			.add_systems(OnEnter(AppState::EnterName), spawn_name_entry_ui)
+			.add_systems(OnExit(AppState::EnterName), despawn_with::<NameEntryUi>)
+			.add_systems(OnEnter(AppState::GameOver), spawn_game_over_ui)
+			.add_systems(OnExit(AppState::GameOver), despawn_with::<GameOverUi>)
+			.add_systems(
+				Update,
+				(
+					update_hud,
+					(name_entry_input, update_name_entry_text)
+						.chain()
+						.run_if(in_state(AppState::EnterName)),
+					(rebind_input, update_rebind_text)
+						.chain()
+						.run_if(in_state(AppState::Controls)),
+				),
+			);
+	}
+}
+
+pub fn despawn_with<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+	for entity in query.iter() {
+		commands.entity(entity).despawn_recursive();
+	}
+}
+
+fn spawn_overlay(
+	commands: &mut Commands,
+	marker: impl Component,
+	lines: &[(String, f32)],
+) -> Entity {
+	commands
+		.spawn(NodeBundle {
+			style: Style {
+				width: Val::Percent(100.0),
+				height: Val::Percent(100.0),
+				position_type: PositionType::Absolute,
+				flex_direction: FlexDirection::Column,
+				justify_content: JustifyContent::Center,
+				align_items: AlignItems::Center,
+				row_gap: Val::Px(12.0),
+				..default()
+			},
+			..default()
+		})
+		.insert(marker)
+		.with_children(|parent| {
+			for (text, font_size) in lines {
+				parent.spawn(TextBundle::from_section(
+					text.clone(),
+					TextStyle {
+						font_size: *font_size,
+						color: UI_TEXT_COLOR,
+						..default()
+					},
+				));
+			}
+		})
+		.id()
+}
+
+fn spawn_main_menu(mut commands: Commands, playback: Option<Res<ReplayPlayback>>) {
+	let mut lines = vec![("Block Bite".to_string(), 60.0)];
+	if playback.is_some() {
+		lines.push(("Press Enter to watch the replay".to_string(), 24.0));
+		lines.push(("+ and - change the playback speed".to_string(), 20.0));
+	} else {
+		lines.push(("Press Enter to start".to_string(), 24.0));
+		lines.push(("Press C to change the controls".to_string(), 20.0));
+	}
+	spawn_overlay(&mut commands, MainMenuUi, &lines);
+}
+
+/// What to press for `action`, as "Escape, P or Pad:Start". `None` while nothing is bound.
+fn bound_to(key_map: &KeyMap, action: Action) -> Option<String> {
+	let names = key_map
+		.bindings(action)
+		.iter()
+		.filter_map(|binding| binding.name())
+		.collect::<Vec<String>>();
+	match names.split_last()? {
+		(last, []) => Some(last.clone()),
+		(last, rest) => Some(format!("{} or {last}", rest.join(", "))),
+	}
+}
+
+fn spawn_paused_ui(mut commands: Commands, key_map: Res<KeyMap>) {
+	let mut lines = vec![("Paused".to_string(), 48.0)];
+	if let Some(keys) = bound_to(&key_map, Action::Pause) {
+		lines.push((format!("Press {keys} to resume"), 24.0));
+	}
+	if let Some(keys) = bound_to(&key_map, Action::Restart) {
+		lines.push((format!("Press {keys} to restart"), 20.0));
+	}
+	lines.push(("Press Q to quit".to_string(), 20.0));
+	let overlay = spawn_overlay(&mut commands, PausedUi, &lines);
+	commands
+		.entity(overlay)
+		.insert(BackgroundColor(PAUSE_DIM_COLOR));
+}
+
+fn spawn_game_over_ui(
+	mut commands: Commands,
+	last_game_over: Res<LastGameOver>,
+	high_scores: Res<HighScores>,
+	score: Res<Score>,
+	players: Res<PlayerCount>,
+	key_map: Res<KeyMap>,
+) {
+	let title = match &last_game_over.0 {
+		Some(event) if event.cause == GameOverCause::BoardFull => "You Win".to_string(),
+		Some(event) if players.0 > 1 => match event.winner {
+			Some(winner) => format!("Player {} Wins", winner + 1),
+			None => "Draw".to_string(),
+		},
+		_ => "Game Over".to_string(),
+	};
+	let mut lines = vec![(title, 48.0)];
+	if players.0 > 1 {
+		for (index, player) in score.players.iter().enumerate() {
+			let length = last_game_over
+				.0
+				.as_ref()
+				.map_or(player.length, |event| event.length(index));
+			lines.push((
+				format!(
+					"Player {}: {} points, length {length}",
+					index + 1,
+					player.points,
+				),
+				24.0,
+			));
+		}
+	} else if let Some(event) = &last_game_over.0 {
+		let cause = match event.cause {
+			GameOverCause::Wall => "You hit the wall",
+			GameOverCause::Tail => "You bit your own tail",
+			GameOverCause::Snake => "You ran into another snake",
+			GameOverCause::BoardFull => "The board is full",
+		};
+		lines.push((cause.to_string(), 24.0));
+		lines.push((format!("Length: {}", event.length(0)), 24.0));
+	}
+	if !high_scores.entries.is_empty() {
+		let table = high_scores
+			.entries
+			.iter()
+			.enumerate()
+			.map(|(rank, entry)| format!("{:>2}. {:<12} {:>5}", rank + 1, entry.name, entry.points))
+			.collect::<Vec<String>>()
+			.join("\n");
+		lines.push((table, 16.0));
+	}
+	if let Some(keys) = bound_to(&key_map, Action::Restart) {
+		lines.push((format!("Press {keys} to play again"), 20.0));
+	}
+	lines.push(("Press Enter to return to the menu".to_string(), 20.0));
+	spawn_overlay(&mut commands, GameOverUi, &lines);
+}
+
+fn spawn_name_entry_ui(mut commands: Commands, mut name_entry: ResMut<NameEntry>) {
+	name_entry.0.clear();
+	let overlay = spawn_overlay(
+		&mut commands,
+		NameEntryUi,
+		&[
+			("New High Score!".to_string(), 48.0),
+			("Type your name and press Enter".to_string(), 20.0),
+		],
+	);
+	commands.entity(overlay).with_children(|parent| {
+		parent
+			.spawn(TextBundle::from_section(
+				"_",
+				TextStyle {
+					font_size: 32.0,
+					color: UI_TEXT_COLOR,
+					..default()
+				},
+			))
+			.insert(NameEntryText);
+	});
+}
+
+fn name_entry_input(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut char_reader: EventReader<ReceivedCharacter>,
+	mut name_entry: ResMut<NameEntry>,
+	mut high_scores: ResMut<HighScores>,
+	score: Res<Score>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	for event in char_reader.read() {
+		for c in event.char.chars() {
+			if (c.is_alphanumeric() || c == ' ')
+				&& name_entry.0.chars().count() < HIGH_SCORE_NAME_LEN
+			{
+				name_entry.0.push(c);
+			}
+		}
+	}
+	if keyboard_input.just_pressed(KeyCode::Backspace) {
+		name_entry.0.pop();
+	}
+	if keyboard_input.just_pressed(KeyCode::Enter) {
+		let name = name_entry.0.trim();
+		high_scores.insert(HighScoreEntry {
+			name: if name.is_empty() { "Anonymous" } else { name }.to_string(),
+			points: score.player(0).points,
+			length: score.player(0).length,
+		});
+		if let Err(err) = high_scores.save() {
+			warn!("Could not save the high-score table: {err}");
+		}
+		next_state.set(AppState::GameOver);
+	}
+}
+
+fn update_name_entry_text(
+	name_entry: Res<NameEntry>,
+	mut name_text: Query<&mut Text, With<NameEntryText>>,
+) {
+	if !name_entry.is_changed() {
+		return;
+	}
+	for mut text in name_text.iter_mut() {
+		text.sections[0].value = format!("{}_", name_entry.0);
+	}
+}
+
+fn spawn_rebind_ui(mut commands: Commands, mut rebinding: ResMut<Rebinding>) {
+	*rebinding = Rebinding::default();
+	let overlay = spawn_overlay(
+		&mut commands,
+		RebindUi,
+		&[
+			("Controls".to_string(), 48.0),
+			(
+				"Up/Down to select, Enter to add a key, Backspace to clear, Escape to go back"
+					.to_string(),
+				18.0,
+			),
+		],
+	);
+	commands.entity(overlay).with_children(|parent| {
+		parent
+			.spawn(TextBundle::from_section(
+				"",
+				TextStyle {
+					font_size: 16.0,
+					color: UI_TEXT_COLOR,
+					..default()
+				},
+			))
+			.insert(RebindText);
+	});
+}
+
+fn rebind_input(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+	mut key_map: ResMut<KeyMap>,
+	mut rebinding: ResMut<Rebinding>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	let actions = Action::all(MAX_PLAYERS).collect::<Vec<Action>>();
+	let action = actions[rebinding.selected];
+	if rebinding.listening {
+		if keyboard_input.just_pressed(KeyCode::Escape) {
+			rebinding.listening = false;
+			rebinding.message.clear();
+			return;
+		}
+		let pressed = keyboard_input
+			.get_just_pressed()
+			.next()
+			.map(|key| Binding::Key(*key))
+			.or_else(|| {
+				gamepad_buttons
+					.get_just_pressed()
+					.next()
+					.map(|button| Binding::Button(button.button_type))
+			});
+		let Some(binding) = pressed else {
+			return;
+		};
+		rebinding.listening = false;
+		rebinding.message = match key_map.bind(action, binding) {
+			Ok(()) => String::new(),
+			Err(BindError::Conflict(other)) => format!(
+				"{} is already bound to {}",
+				binding.name().unwrap_or_default(),
+				other.label()
+			),
+			Err(BindError::Unnamed) => "That key can't be bound".to_string(),
+		};
+		return;
+	}
+	let count = actions.len();
+	if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+		rebinding.selected = (rebinding.selected + count - 1) % count;
+		rebinding.message.clear();
+	} else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+		rebinding.selected = (rebinding.selected + 1) % count;
+		rebinding.message.clear();
+	} else if keyboard_input.just_pressed(KeyCode::Enter) {
+		rebinding.listening = true;
+		rebinding.message = format!(
+			"Press a key or button for {}, Escape to cancel",
+			action.label()
+		);
+	} else if keyboard_input.any_just_pressed([KeyCode::Backspace, KeyCode::Delete]) {
+		key_map.clear(action);
+		rebinding.message.clear();
+	} else if keyboard_input.just_pressed(KeyCode::Escape) {
+		next_state.set(AppState::MainMenu);
+	}
+}
+
+fn update_rebind_text(
+	key_map: Res<KeyMap>,
+	rebinding: Res<Rebinding>,
+	mut rebind_text: Query<(&mut Text, Ref<RebindText>)>,
+) {
+	for (mut text, marker) in rebind_text.iter_mut() {
+		if !key_map.is_changed() && !rebinding.is_changed() && !marker.is_added() {
+			continue;
+		}
+		let mut lines = Action::all(MAX_PLAYERS)
+			.enumerate()
+			.map(|(index, action)| {
+				let names = key_map
+					.bindings(action)
+					.iter()
+					.filter_map(|binding| binding.name())
+					.collect::<Vec<String>>();
+				let cursor = if index == rebinding.selected {
+					">"
+				} else {
+					" "
+				};
+				format!("{cursor} {}: {}", action.label(), names.join(", "))
+			})
+			.collect::<Vec<String>>();
+		lines.push(String::new());
+		lines.push(rebinding.message.clone());
+		text.sections[0].value = lines.join("\n");
+	}
+}
+
+fn save_key_map(key_map: Res<KeyMap>) {
+	if let Err(err) = key_map.save() {
+		warn!("Could not save the key map: {err}");
+	}
+}
+
+fn spawn_hud(mut commands: Commands) {
+	commands
+		.spawn(NodeBundle {
+			style: Style {
+				width: Val::Percent(100.0),
+				height: Val::Px(HUD_HEIGHT),
+				position_type: PositionType::Absolute,
+				top: Val::Px(0.0),
+				align_items: AlignItems::Center,
+				padding: UiRect::horizontal(Val::Px(8.0)),
+				..default()
+			},
+			background_color: HUD_BACKGROUND_COLOR.into(),
+			..default()
+		})
+		.insert(HudUi)
+		.with_children(|parent| {
+			parent
+				.spawn(TextBundle::from_section(
+					"",
+					TextStyle {
+						font_size: 18.0,
+						color: UI_TEXT_COLOR,
+						..default()
+					},
+				))
+				.insert(HudText);
+		});
+}
+
+fn update_hud(
+	score: Res<Score>,
+	rng: Res<GameRng>,
+	speed: Res<GameSpeed>,
+	mut hud_text: Query<(&mut Text, Ref<HudText>)>,
+) {
+	for (mut text, marker) in hud_text.iter_mut() {
+		if !score.is_changed() && !marker.is_added() {
+			continue;
+		}
+		let players = match score.players[..] {
+			[player] => format!(
+				"Score {}   Food {}   Length {}",
+				player.points, player.food_eaten, player.length
+			),
+			_ => score
+				.players
+				.iter()
+				.enumerate()
+				.map(|(index, player)| {
+					format!("P{} {} ({})", index + 1, player.points, player.length)
+				})
+				.collect::<Vec<String>>()
+				.join("   "),
+		};
+		let seconds = score.elapsed.as_secs();
+		text.sections[0].value = format!(
+			"{players}   Speed {}   Time {}:{:02}   Seed {}",
+			speed.level + 1,
+			seconds / 60,
+			seconds % 60,
+			rng.seed()
+		);
+	}
+}
@@ -0,0 +1,64 @@
+use block_bite::{
+	arena::{Arena, WallLayout},
+	autopilot::autopilot_run,
+	components::Position,
+	settings::Settings,
+};
+
+fn fills(width: u32, height: u32) {
+	let report = autopilot_run(
+		Settings {
+			arena: Arena {
+				width,
+				height,
+				..Arena::default()
+			},
+			seed: Some(7),
+			..Settings::default()
+		},
+		1_000_000,
+	)
+	.unwrap();
+	assert!(report.filled, "{width}x{height}: {report:?}");
+	assert_eq!(report.length, (width * height) as usize);
+}
+
+#[test]
+fn autopilot_fills_a_small_board() {
+	fills(4, 4);
+}
+
+#[test]
+fn autopilot_fills_the_default_board() {
+	fills(10, 10);
+}
+
+#[test]
+fn autopilot_fills_a_board_with_an_odd_side() {
+	fills(5, 6);
+}
+
+#[test]
+fn autopilot_refuses_boards_without_a_cycle() {
+	let odd = Arena {
+		width: 5,
+		height: 5,
+		..Arena::default()
+	};
+	assert!(autopilot_run(
+		Settings {
+			arena: odd,
+			..Settings::default()
+		},
+		100
+	)
+	.is_none());
+	assert!(autopilot_run(
+		Settings {
+			walls: WallLayout(vec![Position { x: 1, y: 1 }]),
+			..Settings::default()
+		},
+		100
+	)
+	.is_none());
+}
@@ -0,0 +1,86 @@
+use std::time::Instant;
+
+use block_bite::{
+	ai::{Opponents, StrategyKind},
+	gym::{Env, Rewards, FEATURE_LEN, OTHER_SNAKES, OWN_HEAD},
+	movement::{GameOverCause, MAX_PLAYERS, START_LENGTH},
+	settings::Settings,
+	Direction,
+};
+
+#[test]
+fn steps_until_the_snake_runs_into_a_wall() {
+	let mut env = Env::new(Settings::default(), Rewards::default());
+	let observation = env.reset(42);
+	let [channels, height, width] = env.grid_shape();
+	assert_eq!(observation.grid.len(), channels * height * width);
+	assert_eq!(observation.features.len(), FEATURE_LEN);
+
+	let mut steps = 0;
+	let (reward, info) = loop {
+		let (observation, reward, done, info) = env.step(Direction::Up);
+		steps += 1;
+		assert_eq!(observation.grid.len(), channels * height * width);
+		assert_eq!(observation.features.len(), FEATURE_LEN);
+		if done {
+			break (reward, info);
+		}
+		assert!(steps < 100, "the snake never crashed");
+	};
+	// From the middle row of a 10x10 arena, the fifth step up leaves it.
+	assert_eq!(steps, 5);
+	assert_eq!(info.cause, Some(GameOverCause::Wall));
+	assert_eq!(reward, Rewards::default().death);
+
+	// Done until the next reset.
+	let (_, reward, done, _) = env.step(Direction::Up);
+	assert!(done);
+	assert_eq!(reward, 0.0);
+	env.reset(42);
+	let (_, _, done, info) = env.step(Direction::Up);
+	assert!(!done);
+	assert_eq!(info.ticks, 1);
+}
+
+#[test]
+fn opponents_that_do_not_fit_are_dropped() {
+	let mut env = Env::new(
+		Settings {
+			opponents: Opponents(vec![StrategyKind::Greedy; 8]),
+			..Settings::default()
+		},
+		Rewards::default(),
+	);
+	let observation = env.reset(7);
+	let [_, height, width] = env.grid_shape();
+	let plane = |channel: usize| &observation.grid[channel * height * width..][..height * width];
+	let cells = |channel: usize| plane(channel).iter().filter(|cell| **cell == 1.0).count();
+	assert_eq!(cells(OWN_HEAD), 1);
+	assert_eq!(cells(OTHER_SNAKES), (MAX_PLAYERS - 1) * START_LENGTH);
+}
+
+/// Wall-clock bound, run it on an optimized build with `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn steps_quickly() {
+	const STEPS: u32 = 10_000;
+	let mut env = Env::new(Settings::default(), Rewards::default());
+	let directions = [
+		Direction::Up,
+		Direction::Right,
+		Direction::Down,
+		Direction::Left,
+	];
+	let mut seed = 0;
+	env.reset(seed);
+	let start = Instant::now();
+	for step in 0..STEPS {
+		let direction = directions[(step / 3) as usize % directions.len()];
+		if env.step(direction).2 {
+			seed += 1;
+			env.reset(seed);
+		}
+	}
+	let per_second = f64::from(STEPS) / start.elapsed().as_secs_f64();
+	assert!(per_second > 1000.0, "{per_second:.0} steps per second");
+}
@@ -0,0 +1,27 @@
+use bevy::ecs::schedule::State;
+use block_bite::{
+	headless::headless_app, movement::GameOverCause, round::LastGameOver, settings::Settings,
+	tick::TickCounter, AppState,
+};
+
+#[test]
+fn snake_left_alone_runs_into_the_top_wall() {
+	let mut app = headless_app(Settings {
+		seed: Some(42),
+		..Settings::default()
+	});
+	for _ in 0..100 {
+		app.update();
+		if *app.world.resource::<State<AppState>>().get() == AppState::GameOver {
+			break;
+		}
+	}
+
+	let last_game_over = app.world.resource::<LastGameOver>();
+	assert_eq!(
+		last_game_over.0.as_ref().map(|event| event.cause),
+		Some(GameOverCause::Wall)
+	);
+	// From the middle row of a 10x10 arena, the fifth step up leaves it.
+	assert_eq!(app.world.resource::<TickCounter>().0, 5);
+}